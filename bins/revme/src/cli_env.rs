@@ -1,12 +1,14 @@
 use std::str::FromStr;
 
 use bytes::Bytes;
-use primitive_types::{H160, U256};
-use revm::{Env, TransactTo};
+use primitive_types::{H160, H256, U256};
+use revm::{Env, SpecId, TransactTo};
 use structopt::StructOpt;
 
 #[derive(StructOpt, Clone, Debug)]
 pub struct CliEnv {
+    #[structopt(flatten)]
+    cfg: CliEnvCfg,
     #[structopt(flatten)]
     block: CliEnvBlock,
     #[structopt(flatten)]
@@ -29,50 +31,67 @@ macro_rules! local_fill {
 impl From<CliEnv> for Env {
     fn from(from: CliEnv) -> Self {
         let mut env = Env::default();
-        local_fill!(env.block.gas_limit, from.block.block_gas_limit, U256::from);
-        local_fill!(env.block.number, from.block.number, U256::from);
+        local_fill!(env.cfg.chain_id, from.cfg.cfg_chain_id);
+        local_fill!(env.cfg.spec_id, from.cfg.spec_id);
+
+        local_fill!(env.block.gas_limit, from.block.block_gas_limit);
+        local_fill!(env.block.number, from.block.number);
         local_fill!(env.block.coinbase, from.block.coinbase);
-        local_fill!(env.block.timestamp, from.block.timestamp, U256::from);
-        local_fill!(env.block.difficulty, from.block.difficulty, U256::from);
-        local_fill!(env.block.basefee, from.block.basefee, U256::from);
+        local_fill!(env.block.timestamp, from.block.timestamp);
+        local_fill!(env.block.difficulty, from.block.difficulty);
+        local_fill!(env.block.prevrandao, from.block.prevrandao, Some);
+        local_fill!(env.block.basefee, from.block.basefee);
 
         local_fill!(env.tx.caller, from.tx.caller);
         local_fill!(env.tx.gas_limit, from.tx.tx_gas_limit);
-        local_fill!(env.tx.value, from.tx.value, U256::from);
+        local_fill!(env.tx.gas_price, from.tx.gas_price);
+        local_fill!(env.tx.value, from.tx.value);
         local_fill!(env.tx.data, from.tx.data);
-        env.tx.gas_priority_fee = from.tx.gas_priority_fee.map(U256::from);
+        env.tx.gas_priority_fee = from.tx.gas_priority_fee;
         env.tx.chain_id = from.tx.chain_id;
         env.tx.nonce = from.tx.nonce;
+        env.tx.access_list = from.tx.access_list;
 
         env.tx.transact_to = if let Some(to) = from.tx.transact_to {
             TransactTo::Call(to)
         } else {
             TransactTo::create()
         };
-        //TODO tx access_list
 
         env
     }
 }
 
+#[derive(StructOpt, Clone, Debug)]
+pub struct CliEnvCfg {
+    #[structopt(long = "env.cfg.chain_id", parse(try_from_str = parse_u256))]
+    pub cfg_chain_id: Option<U256>,
+    /// Spec name (for example `london` or `BERLIN`) or its numeric id.
+    #[structopt(long = "env.cfg.spec_id", parse(try_from_str = parse_spec_id))]
+    pub spec_id: Option<SpecId>,
+}
+
 #[derive(StructOpt, Clone, Debug)]
 pub struct CliEnvBlock {
-    #[structopt(long = "env.block.gas_limit")]
-    pub block_gas_limit: Option<u64>,
+    #[structopt(long = "env.block.gas_limit", parse(try_from_str = parse_u256))]
+    pub block_gas_limit: Option<U256>,
     /// somebody call it nonce
-    #[structopt(long = "env.block.number")]
-    pub number: Option<u64>,
+    #[structopt(long = "env.block.number", parse(try_from_str = parse_u256))]
+    pub number: Option<U256>,
     /// Coinbase or miner or address that created and signed the block.
     /// Address where we are going to send gas spend
     #[structopt(long = "env.block.coinbase", parse(try_from_str = parse_h160))]
     pub coinbase: Option<H160>,
-    #[structopt(long = "env.block.timestamp")]
-    pub timestamp: Option<u64>,
-    #[structopt(long = "env.block.difficulty")]
-    pub difficulty: Option<u64>,
+    #[structopt(long = "env.block.timestamp", parse(try_from_str = parse_u256))]
+    pub timestamp: Option<U256>,
+    #[structopt(long = "env.block.difficulty", parse(try_from_str = parse_u256))]
+    pub difficulty: Option<U256>,
+    /// Prevrandao is used after Paris (aka TheMerge) instead of the difficulty value.
+    #[structopt(long = "env.block.prevrandao", parse(try_from_str = parse_h256))]
+    pub prevrandao: Option<H256>,
     /// basefee is added in EIP1559 London upgrade
-    #[structopt(long = "env.block.basefee")]
-    pub basefee: Option<u64>,
+    #[structopt(long = "env.block.basefee", parse(try_from_str = parse_u256))]
+    pub basefee: Option<U256>,
 }
 
 #[derive(StructOpt, Clone, Debug)]
@@ -80,24 +99,29 @@ pub struct CliEnvTx {
     /// Caller or Author or tx signer
     #[structopt(long = "env.tx.caller", parse(try_from_str = parse_h160))]
     pub caller: Option<H160>,
-    #[structopt(long = "env.tx.gas_limit")]
+    #[structopt(long = "env.tx.gas_limit", parse(try_from_str = parse_u64))]
     pub tx_gas_limit: Option<u64>,
-    #[structopt(long = "env.tx.gas_price")]
-    pub gas_price: Option<u64>,
-    #[structopt(long = "env.tx.gas_priority_fee")]
-    pub gas_priority_fee: Option<u64>,
+    #[structopt(long = "env.tx.gas_price", parse(try_from_str = parse_u256))]
+    pub gas_price: Option<U256>,
+    #[structopt(long = "env.tx.gas_priority_fee", parse(try_from_str = parse_u256))]
+    pub gas_priority_fee: Option<U256>,
     #[structopt(long = "env.tx.to", parse(try_from_str = parse_h160))]
     pub transact_to: Option<H160>,
-    #[structopt(long = "env.tx.value")]
-    pub value: Option<u64>,
+    #[structopt(long = "env.tx.value", parse(try_from_str = parse_u256))]
+    pub value: Option<U256>,
     #[structopt(long = "env.tx.data", parse(try_from_str = parse_hex))]
     pub data: Option<Bytes>,
-    #[structopt(long = "env.tx.chain_id")]
+    #[structopt(long = "env.tx.chain_id", parse(try_from_str = parse_u64))]
     pub chain_id: Option<u64>,
-    #[structopt(long = "env.tx.nonce")]
+    #[structopt(long = "env.tx.nonce", parse(try_from_str = parse_u64))]
     pub nonce: Option<u64>,
-    //#[structopt(long = "env.")]
-    //TODO pub access_list: Vec<(H160, Vec<U256>)>,
+    /// Access list item in `address:slot,slot` format. Can be repeated.
+    #[structopt(
+        long = "env.tx.access_list",
+        number_of_values = 1,
+        parse(try_from_str = parse_access_list_item)
+    )]
+    pub access_list: Vec<(H160, Vec<U256>)>,
 }
 
 fn parse_hex(src: &str) -> Result<Bytes, hex::FromHexError> {
@@ -107,3 +131,133 @@ fn parse_hex(src: &str) -> Result<Bytes, hex::FromHexError> {
 pub fn parse_h160(input: &str) -> Result<H160, <H160 as FromStr>::Err> {
     H160::from_str(input)
 }
+
+pub fn parse_h256(input: &str) -> Result<H256, <H256 as FromStr>::Err> {
+    H256::from_str(input)
+}
+
+/// Parse decimal or `0x` prefixed hex number.
+pub fn parse_u256(input: &str) -> Result<U256, String> {
+    match input.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(|e| format!("{:?}", e)),
+        None => U256::from_dec_str(input).map_err(|e| format!("{:?}", e)),
+    }
+}
+
+/// Parse decimal or `0x` prefixed hex number that fits into `u64`.
+pub fn parse_u64(input: &str) -> Result<u64, String> {
+    let value = parse_u256(input)?;
+    if value > U256::from(u64::MAX) {
+        return Err(format!("Number does not fit into u64: {}", input));
+    }
+    Ok(value.as_u64())
+}
+
+/// Parse spec from its name (case insensitive) or from its numeric id.
+pub fn parse_spec_id(input: &str) -> Result<SpecId, String> {
+    if let Ok(id) = input.parse::<u8>() {
+        return SpecId::try_from_u8(id).ok_or_else(|| format!("Unknown spec id: {}", id));
+    }
+    (0..=SpecId::LATEST as u8)
+        .filter_map(SpecId::try_from_u8)
+        .find(|spec_id| format!("{:?}", spec_id).eq_ignore_ascii_case(input))
+        .ok_or_else(|| format!("Unknown spec name: {}", input))
+}
+
+/// Parse access list item in `address:slot,slot` format.
+pub fn parse_access_list_item(input: &str) -> Result<(H160, Vec<U256>), String> {
+    let (address, slots) = input.split_once(':').unwrap_or((input, ""));
+    let address = parse_h160(address).map_err(|e| format!("{:?}", e))?;
+    let slots = slots
+        .split(',')
+        .filter(|slot| !slot.is_empty())
+        .map(parse_u256)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((address, slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_u256_decimal_and_hex() {
+        assert_eq!(parse_u256("1000"), Ok(U256::from(1000)));
+        assert_eq!(parse_u256("0x3e8"), Ok(U256::from(1000)));
+        assert!(parse_u256("3e8").is_err());
+        assert!(parse_u256("0xzz").is_err());
+    }
+
+    #[test]
+    fn parse_u64_decimal_and_hex() {
+        assert_eq!(parse_u64("1000"), Ok(1000));
+        assert_eq!(parse_u64("0x3e8"), Ok(1000));
+        assert_eq!(parse_u64("0xffffffffffffffff"), Ok(u64::MAX));
+        assert!(parse_u64("0x10000000000000000").is_err());
+        assert!(parse_u64("3e8").is_err());
+    }
+
+    #[test]
+    fn tx_numbers_accept_hex() {
+        let env = CliEnv::from_iter_safe([
+            "env",
+            "--env.tx.gas_limit",
+            "0x5208",
+            "--env.tx.chain_id",
+            "0x1",
+            "--env.tx.nonce",
+            "7",
+        ])
+        .unwrap();
+        assert_eq!(env.tx.tx_gas_limit, Some(21_000));
+        assert_eq!(env.tx.chain_id, Some(1));
+        assert_eq!(env.tx.nonce, Some(7));
+        assert!(CliEnv::from_iter_safe(["env", "--env.tx.nonce", "0x1zz"]).is_err());
+    }
+
+    #[test]
+    fn parse_spec_id_name_and_number() {
+        assert_eq!(parse_spec_id("london"), Ok(SpecId::LONDON));
+        assert_eq!(parse_spec_id("LONDON"), Ok(SpecId::LONDON));
+        assert_eq!(
+            parse_spec_id(&(SpecId::BERLIN as u8).to_string()),
+            Ok(SpecId::BERLIN)
+        );
+        assert!(parse_spec_id("unknown").is_err());
+        assert!(parse_spec_id("255").is_err());
+    }
+
+    #[test]
+    fn parse_access_list_item_with_slots() {
+        let address = H160::from_low_u64_be(0xaa);
+        assert_eq!(
+            parse_access_list_item("0x00000000000000000000000000000000000000aa"),
+            Ok((address, Vec::new()))
+        );
+        assert_eq!(
+            parse_access_list_item("0x00000000000000000000000000000000000000aa:1,0x02"),
+            Ok((address, vec![U256::from(1), U256::from(2)]))
+        );
+        assert!(parse_access_list_item("0xaa:1").is_err());
+        assert!(parse_access_list_item("0x00000000000000000000000000000000000000aa:x").is_err());
+    }
+
+    #[test]
+    fn access_list_takes_one_value_per_flag() {
+        let env = CliEnv::from_iter_safe([
+            "env",
+            "--env.tx.access_list",
+            "0x00000000000000000000000000000000000000aa:1",
+            "--env.tx.access_list",
+            "0x00000000000000000000000000000000000000bb",
+        ])
+        .unwrap();
+        assert_eq!(
+            env.tx.access_list,
+            vec![
+                (H160::from_low_u64_be(0xaa), vec![U256::from(1)]),
+                (H160::from_low_u64_be(0xbb), Vec::new()),
+            ]
+        );
+    }
+}
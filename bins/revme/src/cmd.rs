@@ -15,15 +15,15 @@ use thiserror::Error as ThisError;
 pub enum Error {
     #[error("Statetest: {0}")]
     Statetest(statetest::Error),
-    #[error("Generic system error")]
-    SystemError,
+    #[error("Run: {0}")]
+    Run(runner::Error),
 }
 
 impl MainCmd {
     pub fn run(&self) -> Result<(), Error> {
         match self {
             Self::Statetest(cmd) => cmd.run().map_err(Error::Statetest),
-            _ => Ok(()),
+            Self::Run(cmd) => cmd.run().map_err(Error::Run),
         }
     }
 }
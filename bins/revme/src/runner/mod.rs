@@ -1,4 +1,350 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    path::PathBuf,
+};
+
+use bytes::Bytes;
+use primitive_types::{H160, H256};
+use revm::{Account, Bytecode, Env, ExecutionResult, InMemoryDB, TransactOut, TransactTo};
+use sha3::{Digest, Keccak256};
 use structopt::StructOpt;
+use thiserror::Error as ThisError;
+
+use crate::{
+    cli_env::CliEnv,
+    statetest::{models::AccountInfo, trace::CustomPrintTracer},
+};
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error("Either bytecode or --path needs to be set")]
+    MissingBytecode,
+    #[error("Only one of bytecode or --path can be set")]
+    ConflictingBytecode,
+    #[error("Invalid hex bytecode: {0}")]
+    InvalidBytecode(#[from] hex::FromHexError),
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid pre-state json: {0}")]
+    StateDeserialize(#[from] serde_json::Error),
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("Transaction failed: {0}")]
+    Transact(String),
+}
 
+/// Execute single transaction over given bytecode.
+///
+/// If `--env.tx.to` is set, bytecode is set as code of that account and it is called.
+/// Otherwise bytecode is used as init code of create transaction
+/// and `--env.tx.data` is appended to it as constructor arguments.
 #[derive(StructOpt, Debug)]
-pub struct Cmd {}
+pub struct Cmd {
+    /// Hex encoded bytecode.
+    #[structopt(required_unless = "path", conflicts_with = "path")]
+    bytecode: Option<String>,
+    /// Path to the file with hex encoded bytecode.
+    #[structopt(long, parse(from_os_str))]
+    path: Option<PathBuf>,
+    /// Path to the pre-state JSON file. It has the same format as `pre` field of state tests.
+    #[structopt(long, parse(from_os_str))]
+    state: Option<PathBuf>,
+    /// Print opcode trace while executing.
+    #[structopt(long)]
+    trace: bool,
+    #[structopt(flatten)]
+    env: CliEnv,
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let (result, state) = self.execute()?;
+
+        match &result {
+            ExecutionResult::Success {
+                reason,
+                gas_used,
+                gas_refunded,
+                logs,
+                output,
+            } => {
+                println!("Exit reason: Success({:?})", reason);
+                println!("Gas used: {} ({} refunded)", gas_used, gas_refunded);
+                match output {
+                    TransactOut::None => println!("Output: 0x"),
+                    TransactOut::Call(bytes) => println!("Output: 0x{}", hex::encode(bytes)),
+                    TransactOut::Create(bytes, address) => {
+                        println!("Output: 0x{}", hex::encode(bytes));
+                        println!("Created address: {:?}", address);
+                    }
+                }
+                println!("Logs:");
+                for log in logs {
+                    println!(
+                        "  address: {:?} topics: {:?} data: 0x{}",
+                        log.address,
+                        log.topics,
+                        hex::encode(&log.data)
+                    );
+                }
+            }
+            ExecutionResult::Revert { gas_used, output } => {
+                println!("Exit reason: Revert");
+                println!("Gas used: {}", gas_used);
+                println!("Output: 0x{}", hex::encode(output));
+            }
+            ExecutionResult::Halt { reason, gas_used } => {
+                println!("Exit reason: Halt({:?})", reason);
+                println!("Gas used: {}", gas_used);
+            }
+        }
+
+        write_state_diff(&mut std::io::stdout(), state)?;
+        Ok(())
+    }
+
+    /// Load pre-state and bytecode and execute transaction without committing it.
+    fn execute(&self) -> Result<(ExecutionResult, hashbrown::HashMap<H160, Account>), Error> {
+        let bytecode = self.bytecode()?;
+
+        let mut db = InMemoryDB::default();
+        if let Some(path) = &self.state {
+            let pre: HashMap<H160, AccountInfo> =
+                serde_json::from_reader(std::fs::File::open(path)?)?;
+            for (address, info) in pre {
+                let code_hash = H256::from_slice(Keccak256::digest(&info.code).as_slice());
+                db.insert_account_info(
+                    address,
+                    revm::AccountInfo {
+                        balance: info.balance,
+                        nonce: info.nonce,
+                        code_hash,
+                        code: Some(Bytecode::new_raw(info.code)),
+                    },
+                );
+                for (slot, value) in info.storage {
+                    db.insert_account_storage(address, slot, value)
+                        .map_err(|err| Error::Database(format!("{:?}", err)))?;
+                }
+            }
+        }
+
+        let mut env: Env = self.env.clone().into();
+        match env.tx.transact_to {
+            TransactTo::Call(address) => {
+                let mut info = db
+                    .accounts
+                    .get(&address)
+                    .map(|account| account.info.clone())
+                    .unwrap_or_default();
+                info.code_hash = H256::from_slice(Keccak256::digest(&bytecode).as_slice());
+                info.code = Some(Bytecode::new_raw(bytecode));
+                db.insert_account_info(address, info);
+            }
+            TransactTo::Create(_) => {
+                let mut init_code = bytecode.to_vec();
+                init_code.extend_from_slice(&env.tx.data);
+                env.tx.data = Bytes::from(init_code);
+            }
+        }
+
+        let mut evm = revm::new();
+        evm.env = env;
+        evm.database(db);
+
+        if self.trace {
+            evm.inspect(CustomPrintTracer::new())
+        } else {
+            evm.transact()
+        }
+        .map_err(|err| Error::Transact(format!("{:?}", err)))
+    }
+
+    fn bytecode(&self) -> Result<Bytes, Error> {
+        let hex_code = match (&self.bytecode, &self.path) {
+            (Some(_), Some(_)) => return Err(Error::ConflictingBytecode),
+            (None, Some(path)) => std::fs::read_to_string(path)?,
+            (Some(bytecode), None) => bytecode.clone(),
+            (None, None) => return Err(Error::MissingBytecode),
+        };
+        let hex_code = hex_code.trim();
+        let hex_code = hex_code.strip_prefix("0x").unwrap_or(hex_code);
+        Ok(Bytes::from(hex::decode(hex_code)?))
+    }
+}
+
+/// Write changed accounts and their changed storage, sorted by address and slot.
+fn write_state_diff(
+    out: &mut impl Write,
+    state: impl IntoIterator<Item = (H160, Account)>,
+) -> std::io::Result<()> {
+    writeln!(out, "State diff:")?;
+    let state: BTreeMap<_, _> = state.into_iter().collect();
+    for (address, account) in state {
+        writeln!(
+            out,
+            "  {:?}: balance: {} nonce: {} code_hash: {:?} destroyed: {}",
+            address,
+            account.info.balance,
+            account.info.nonce,
+            account.info.code_hash,
+            account.is_destroyed,
+        )?;
+        let storage: BTreeMap<_, _> = account
+            .storage
+            .into_iter()
+            .filter(|(_, v)| v.is_changed())
+            .collect();
+        for (slot, value) in storage {
+            writeln!(
+                out,
+                "    storage {:#x}: {:#x} -> {:#x}",
+                slot,
+                value.original_value(),
+                value.present_value()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitive_types::U256;
+
+    fn cmd(args: &[&str]) -> Result<Cmd, structopt::clap::Error> {
+        Cmd::from_iter_safe(core::iter::once("run").chain(args.iter().copied()))
+    }
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("revme-run-{}-{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn call_bytecode() {
+        // SSTORE(0, 0x42), RETURN 32 bytes of memory where 0x42 is stored.
+        let (result, state) = cmd(&[
+            "6042600055604260005260206000f3",
+            "--env.tx.to",
+            "0x00000000000000000000000000000000000000aa",
+        ])
+        .unwrap()
+        .execute()
+        .unwrap();
+
+        assert_eq!(
+            result.output().map(|out| U256::from_big_endian(out)),
+            Some(U256::from(0x42))
+        );
+        let account = &state[&H160::from_low_u64_be(0xaa)];
+        assert_eq!(
+            account.storage[&U256::zero()].present_value(),
+            U256::from(0x42)
+        );
+    }
+
+    #[test]
+    fn create_with_constructor_arguments() {
+        // Return constructor argument appended to init code as runtime code.
+        let (result, state) = cmd(&["6001600a5f3960015ff3", "--env.tx.data", "fe"])
+            .unwrap()
+            .execute()
+            .unwrap();
+        let address = match result {
+            ExecutionResult::Success {
+                output: TransactOut::Create(_, Some(address)),
+                ..
+            } => address,
+            result => panic!("unexpected result {:?}", result),
+        };
+        assert_eq!(
+            state[&address].info.code.as_ref().unwrap().bytes()[..1],
+            [0xfe]
+        );
+    }
+
+    #[test]
+    fn bytecode_from_path_and_pre_state() {
+        // Return SLOAD(1) of the called account.
+        let path = temp_file("code", "0x60015460005260206000f3\n");
+        let state = temp_file(
+            "state",
+            r#"{"0x00000000000000000000000000000000000000aa": {
+                "balance": "0x00", "code": "0x", "nonce": "0x00", "storage": {"0x01": "0x07"}
+            }}"#,
+        );
+        let (result, _) = cmd(&[
+            "--path",
+            path.to_str().unwrap(),
+            "--state",
+            state.to_str().unwrap(),
+            "--env.tx.to",
+            "0x00000000000000000000000000000000000000aa",
+        ])
+        .unwrap()
+        .execute()
+        .unwrap();
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(state).unwrap();
+
+        assert_eq!(
+            result.output().map(|out| U256::from_big_endian(out)),
+            Some(U256::from(7))
+        );
+    }
+
+    #[test]
+    fn reject_bytecode_and_path() {
+        assert!(cmd(&["0x00", "--path", "code.hex"]).is_err());
+        assert!(cmd(&[]).is_err());
+    }
+
+    #[test]
+    fn bytecode_after_access_list() {
+        let cmd = cmd(&[
+            "--env.tx.access_list",
+            "0x00000000000000000000000000000000000000aa",
+            "6000",
+        ])
+        .unwrap();
+        assert_eq!(cmd.bytecode.as_deref(), Some("6000"));
+    }
+
+    #[test]
+    fn state_diff_is_sorted() {
+        // SSTORE(3, 3), SSTORE(1, 1), SSTORE(2, 2)
+        let (_, state) = cmd(&[
+            "600380556001805560028055",
+            "--env.tx.to",
+            "0x00000000000000000000000000000000000000aa",
+            "--env.tx.caller",
+            "0x00000000000000000000000000000000000000bb",
+        ])
+        .unwrap()
+        .execute()
+        .unwrap();
+        let mut out = Vec::new();
+        write_state_diff(&mut out, state).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = out
+            .lines()
+            .map(|line| line.split(':').next().unwrap().trim())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "State diff",
+                "0x0000000000000000000000000000000000000000",
+                "0x00000000000000000000000000000000000000aa",
+                "storage 0x1",
+                "storage 0x2",
+                "storage 0x3",
+                "0x00000000000000000000000000000000000000bb",
+            ]
+        );
+    }
+}
@@ -68,6 +68,8 @@ pub struct Env {
     pub current_timestamp: U256,
     pub current_base_fee: Option<U256>,
     pub previous_hash: H256,
+    pub current_random: Option<H256>,
+    pub current_excess_blob_gas: Option<U256>,
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize)]
@@ -85,6 +87,8 @@ pub struct TransactionParts {
     pub value: Vec<U256>,
     pub max_fee_per_gas: Option<U256>,
     pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub blob_versioned_hashes: Vec<H256>,
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Clone)]
@@ -20,7 +20,10 @@ pub enum SpecName {
     Berlin,            //done
     BerlinToLondonAt5, // done
     London,            // done
-    Merge,             //done
+    #[serde(alias = "Paris")]
+    Merge, //done
+    Shanghai,
+    Cancun,
 }
 
 impl SpecName {
@@ -38,6 +41,8 @@ impl SpecName {
             Self::Berlin => SpecId::BERLIN,
             Self::London | Self::BerlinToLondonAt5 => SpecId::LONDON,
             Self::Merge => SpecId::MERGE,
+            Self::Shanghai => SpecId::SHANGHAI,
+            Self::Cancun => SpecId::CANCUN,
             Self::ByzantiumToConstantinopleAt5 | Self::Constantinople => {
                 panic!("Overriden with PETERSBURG")
             } //_ => panic!("Conversion failed"),
@@ -1,8 +1,7 @@
 use std::{
-    collections::HashMap,
     ffi::OsStr,
+    fmt::Debug,
     path::{Path, PathBuf},
-    str::FromStr,
     sync::{atomic::AtomicBool, Arc, Mutex},
     time::{Duration, Instant},
 };
@@ -11,7 +10,10 @@ use sha3::{Digest, Keccak256};
 
 use indicatif::ProgressBar;
 use primitive_types::{H160, H256, U256};
-use revm::{db::AccountState, Bytecode, CreateScheme, Env, ExecutionResult, SpecId, TransactTo};
+use revm::{
+    db::AccountState, precompiles::secret_key_to_address, Bytecode, CreateScheme, EVMError, Env,
+    ExecutionResult, SignedTransaction, SpecId, TransactTo,
+};
 use std::sync::atomic::Ordering;
 use walkdir::{DirEntry, WalkDir};
 
@@ -35,8 +37,27 @@ pub enum TestError {
     SerdeDeserialize(#[from] serde_json::Error),
     #[error("Internal system error")]
     SystemError,
-    #[error("Unknown private key: {private_key:?}")]
-    UnknownPrivateKey { private_key: H256 },
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("Invalid private key: {private_key:?}")]
+    InvalidPrivateKey { private_key: H256 },
+    #[error("Missing private key of transaction sender")]
+    MissingPrivateKey,
+}
+
+/// Derive address of the transaction sender from its secp256k1 private key.
+fn caller_address(secret_key: Option<H256>) -> Result<H160, TestError> {
+    let private_key = secret_key.ok_or(TestError::MissingPrivateKey)?;
+    secret_key_to_address(private_key.as_fixed_bytes())
+        .ok_or(TestError::InvalidPrivateKey { private_key })
+}
+
+/// Invalid transactions are expected by tests, only database errors fail the test run.
+fn check_database_error<T, E: Debug>(result: &Result<T, EVMError<E>>) -> Result<(), TestError> {
+    match result {
+        Err(EVMError::Database(err)) => Err(TestError::Database(format!("{:?}", err))),
+        _ => Ok(()),
+    }
 }
 
 pub fn find_all_json_tests(path: &Path) -> Vec<PathBuf> {
@@ -54,23 +75,12 @@ pub fn execute_test_suit(path: &Path, elapsed: &Arc<Mutex<Duration>>) -> Result<
     if path.file_name() == Some(OsStr::new("ValueOverflow.json")) {
         return Ok(());
     }
-    // txbyte is of type 02 and we dont parse tx bytes for this test to fail.
-    if path.file_name() == Some(OsStr::new("typeTwoBerlin.json")) {
-        return Ok(());
-    }
-    // Test checks if nonce overflows. We are handling this correctly but we are not parsing exception in testsuite
-    // There are more nonce overflow tests that are in internal call/create, and those tests are passing and are enabled.
-    if path.file_name() == Some(OsStr::new("CreateTransactionHighNonce.json")) {
-        return Ok(());
-    }
-
-    // Skip test where basefee/accesslist/diffuculty is present but it shouldn't be supported in London/Berlin/TheMerge.
+    // Skip test where basefee/accesslist is present but it shouldn't be supported in London/Berlin.
     // https://github.com/ethereum/tests/blob/5b7e1ab3ffaf026d99d20b17bb30f533a2c80c8b/GeneralStateTests/stExample/eip1559.json#L130
     // It is expected to not execute these tests.
     if path.file_name() == Some(OsStr::new("accessListExample.json"))
         || path.file_name() == Some(OsStr::new("basefeeExample.json"))
         || path.file_name() == Some(OsStr::new("eip1559.json"))
-        || path.file_name() == Some(OsStr::new("mergeTest.json"))
     {
         return Ok(());
     }
@@ -88,41 +98,6 @@ pub fn execute_test_suit(path: &Path, elapsed: &Arc<Mutex<Duration>>) -> Result<
     let json_reader = std::fs::read(path).unwrap();
     let suit: TestSuit = serde_json::from_reader(&*json_reader)?;
 
-    let map_caller_keys: HashMap<_, _> = vec![
-        (
-            H256::from_str("0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8")
-                .unwrap(),
-            H160::from_str("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap(),
-        ),
-        (
-            H256::from_str("0xc85ef7d79691fe79573b1a7064c19c1a9819ebdbd1faaab1a8ec92344438aaf4")
-                .unwrap(),
-            H160::from_str("0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826").unwrap(),
-        ),
-        (
-            H256::from_str("0x044852b2a670ade5407e78fb2863c51de9fcb96542a07186fe3aeda6bb8a116d")
-                .unwrap(),
-            H160::from_str("0x82a978b3f5962a5b0957d9ee9eef472ee55b42f1").unwrap(),
-        ),
-        (
-            H256::from_str("0x6a7eeac5f12b409d42028f66b0b2132535ee158cfda439e3bfdd4558e8f4bf6c")
-                .unwrap(),
-            H160::from_str("0xc9c5a15a403e41498b6f69f6f89dd9f5892d21f7").unwrap(),
-        ),
-        (
-            H256::from_str("0xa95defe70ebea7804f9c3be42d20d24375e2a92b9d9666b832069c5f3cd423dd")
-                .unwrap(),
-            H160::from_str("0x3fb1cd2cd96c6d5c0b5eb3322d807b34482481d4").unwrap(),
-        ),
-        (
-            H256::from_str("0xfe13266ff57000135fb9aa854bbfe455d8da85b21f626307bf3263a0c2a8e7fe")
-                .unwrap(),
-            H160::from_str("0xdcc5ba93a1ed7e045690d722f2bf460a51c61415").unwrap(),
-        ),
-    ]
-    .into_iter()
-    .collect();
-
     for (name, unit) in suit.0.into_iter() {
         // Create database and insert cache
         let mut database = revm::InMemoryDB::default();
@@ -150,20 +125,22 @@ pub fn execute_test_suit(path: &Path, elapsed: &Arc<Mutex<Duration>>) -> Result<
         env.block.gas_limit = unit.env.current_gas_limit;
         env.block.basefee = unit.env.current_base_fee.unwrap_or_default();
         env.block.difficulty = unit.env.current_difficulty;
+        env.block.prevrandao = unit.env.current_random;
+        env.block.excess_blob_gas = unit
+            .env
+            .current_excess_blob_gas
+            .map(|excess_blob_gas| excess_blob_gas.try_into().unwrap_or(u64::MAX));
 
         //tx env
-        env.tx.caller =
-            if let Some(caller) = map_caller_keys.get(&unit.transaction.secret_key.unwrap()) {
-                *caller
-            } else {
-                let private_key = unit.transaction.secret_key.unwrap();
-                return Err(TestError::UnknownPrivateKey { private_key });
-            };
+        env.tx.caller = caller_address(unit.transaction.secret_key)?;
         env.tx.gas_price = unit
             .transaction
             .gas_price
             .unwrap_or_else(|| unit.transaction.max_fee_per_gas.unwrap_or_default());
         env.tx.gas_priority_fee = unit.transaction.max_priority_fee_per_gas;
+        // nonce that does not fit in u64 is not valid and it is going to be rejected as overflow.
+        env.tx.nonce = Some(u64::try_from(unit.transaction.nonce).unwrap_or(u64::MAX));
+        env.tx.blob_hashes = unit.transaction.blob_versioned_hashes.clone();
 
         // post and execution
         for (spec_name, tests) in unit.post {
@@ -223,19 +200,26 @@ pub fn execute_test_suit(path: &Path, elapsed: &Arc<Mutex<Duration>>) -> Result<
                 let mut evm = revm::new();
                 evm.database(&mut database_cloned);
                 evm.env = env.clone();
-                // do the deed
+                // Typed transaction that is not yet enabled is rejected before execution and state stays unchanged.
+                let tx_disabled = test
+                    .txbytes
+                    .as_ref()
+                    .and_then(|txbytes| SignedTransaction::decode(txbytes).ok())
+                    .map(|tx| !tx.tx_type.is_enabled(env.cfg.spec_id))
+                    .unwrap_or_default();
 
-                let timer = Instant::now();
-                let ExecutionResult {
-                    exit_reason,
-                    gas_used,
-                    gas_refunded,
-                    logs,
-                    ..
-                } = evm.transact_commit();
-                let timer = timer.elapsed();
+                // do the deed
+                let exec_result = if !tx_disabled {
+                    let timer = Instant::now();
+                    let exec_result = evm.transact_commit();
+                    let timer = timer.elapsed();
+                    check_database_error(&exec_result)?;
 
-                *elapsed.lock().unwrap() += timer;
+                    *elapsed.lock().unwrap() += timer;
+                    Some(exec_result)
+                } else {
+                    None
+                };
 
                 let is_legacy = !SpecId::enabled(evm.env.cfg.spec_id, SpecId::SPURIOUS_DRAGON);
                 let db = evm.db().unwrap();
@@ -250,6 +234,10 @@ pub fn execute_test_suit(path: &Path, elapsed: &Arc<Mutex<Duration>>) -> Result<
                         })
                         .map(|(k, v)| (*k, v.clone())),
                 );
+                let logs = match &exec_result {
+                    Some(Ok(ExecutionResult::Success { logs, .. })) => logs.clone(),
+                    _ => Vec::new(),
+                };
                 let logs_root = log_rlp_hash(logs);
                 if test.hash != state_root || test.logs != logs_root {
                     println!(
@@ -258,12 +246,12 @@ pub fn execute_test_suit(path: &Path, elapsed: &Arc<Mutex<Duration>>) -> Result<
                     );
                     let mut database_cloned = database.clone();
                     evm.database(&mut database_cloned);
-                    evm.inspect_commit(CustomPrintTracer::new());
+                    check_database_error(&evm.inspect_commit(CustomPrintTracer::new()))?;
                     let db = evm.db().unwrap();
                     println!("{:?} UNIT_TEST:{}\n", path, name);
                     println!(
-                        "fail reson: {:?} {:?} UNIT_TEST:{}\n gas:{:?} ({:?} refunded)",
-                        exit_reason, path, name, gas_used, gas_refunded,
+                        "fail reson: {:?} {:?} UNIT_TEST:{}\n",
+                        exec_result, path, name,
                     );
                     println!("\nApplied state:{:?}\n", db);
                     println!("\nStateroot: {:?}\n", state_root);
@@ -328,3 +316,28 @@ pub fn run(test_files: Vec<PathBuf>) -> Result<(), TestError> {
     println!("Finished execution. Time:{:?}", elapsed.lock().unwrap());
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn caller_address_from_private_key() {
+        let private_key =
+            H256::from_str("0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8")
+                .unwrap();
+        assert_eq!(
+            caller_address(Some(private_key)).unwrap(),
+            H160::from_str("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap()
+        );
+        assert!(matches!(
+            caller_address(Some(H256::zero())),
+            Err(TestError::InvalidPrivateKey { .. })
+        ));
+        assert!(matches!(
+            caller_address(None),
+            Err(TestError::MissingPrivateKey)
+        ));
+    }
+}
@@ -1,12 +1,12 @@
 use bytes::Bytes;
-use primitive_types::H160;
+use primitive_types::{H160, U256};
 pub use revm::Inspector;
 use revm::{
     opcode::{self},
     CallInputs, CreateInputs, Database, EVMData, Gas, GasInspector, Return,
 };
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct CustomPrintTracer {
     gas_inspector: GasInspector,
 }
@@ -135,8 +135,7 @@ impl<DB: Database> Inspector<DB> for CustomPrintTracer {
         (Return::Continue, None, Gas::new(0), Bytes::new())
     }
 
-    fn selfdestruct(&mut self) {
-        //, address: H160, target: H160) {
-        println!("SELFDESTRUCT on "); //{:?} target: {:?}", address, target);
+    fn selfdestruct(&mut self, address: H160, target: H160, value: U256) {
+        println!("SELFDESTRUCT on {address:?} target: {target:?} value: {value}");
     }
 }
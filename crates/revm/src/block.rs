@@ -0,0 +1,599 @@
+use crate::{
+    journaled_state::{State, StorageSlot},
+    Account, BlockEnv, Database, DatabaseCommit, EVMError, ExecutionResult, InvalidTransaction,
+    Log, SpecId, TxEnv,
+};
+use alloc::vec::Vec;
+use hashbrown::hash_map::Entry;
+use primitive_types::{H160, U256};
+use sha3::{Digest, Keccak256};
+
+mod dao;
+
+pub use dao::{DAO_DRAIN_LIST, DAO_HARDFORK_BENEFICIARY, DAO_HARDFORK_BLOCK};
+
+/// Number of bytes in logs bloom filter.
+pub const BLOOM_SIZE: usize = 256;
+
+/// 2048 bit logs bloom filter of the receipt or the block.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Bloom(pub [u8; BLOOM_SIZE]);
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self([0; BLOOM_SIZE])
+    }
+}
+
+impl core::fmt::Debug for Bloom {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Bloom(0x")?;
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl AsRef<[u8]> for Bloom {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Bloom {
+    /// Add log address and topics to the bloom.
+    pub fn accrue_log(&mut self, log: &Log) {
+        self.accrue(log.address.as_bytes());
+        for topic in log.topics.iter() {
+            self.accrue(topic.as_bytes());
+        }
+    }
+
+    /// Add input to the bloom. Three bits are set from the first six bytes of input hash.
+    pub fn accrue(&mut self, input: &[u8]) {
+        let hash = Keccak256::digest(input);
+        for i in [0, 2, 4] {
+            let bit = (((hash[i] as usize) << 8) | hash[i + 1] as usize) & (BLOOM_SIZE * 8 - 1);
+            self.0[BLOOM_SIZE - 1 - bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    /// Add all bits of other bloom, used to create block bloom from receipt blooms.
+    pub fn accrue_bloom(&mut self, other: &Bloom) {
+        for (byte, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *byte |= other;
+        }
+    }
+
+    /// Returns true if all bits of input are set. It can be a false positive.
+    pub fn contains_input(&self, input: &[u8]) -> bool {
+        let mut bloom = Bloom::default();
+        bloom.accrue(input);
+        self.0.iter().zip(bloom.0.iter()).all(|(a, b)| a & b == *b)
+    }
+}
+
+#[cfg(feature = "with-serde")]
+impl serde::Serialize for Bloom {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::models::serde_hex_bytes::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "with-serde")]
+impl<'de> serde::Deserialize<'de> for Bloom {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = crate::models::serde_hex_bytes::deserialize(deserializer)?;
+        let bloom = bytes
+            .as_ref()
+            .try_into()
+            .map_err(|_| serde::de::Error::custom("bloom needs to be 256 bytes"))?;
+        Ok(Self(bloom))
+    }
+}
+
+/// Receipt of the transaction executed in the block.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipt {
+    /// EIP-658 status, true if transaction is successful.
+    pub success: bool,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Gas used by this and all previous transactions in the block.
+    pub cumulative_gas_used: u64,
+    pub logs: Vec<Log>,
+    pub logs_bloom: Bloom,
+}
+
+impl Receipt {
+    pub fn new(result: &ExecutionResult, cumulative_gas_used: u64) -> Self {
+        let logs = result.logs().to_vec();
+        let mut logs_bloom = Bloom::default();
+        logs.iter().for_each(|log| logs_bloom.accrue_log(log));
+        Self {
+            success: result.is_success(),
+            gas_used: result.gas_used(),
+            cumulative_gas_used,
+            logs,
+            logs_bloom,
+        }
+    }
+}
+
+/// Bloom of the block, it contains blooms of all receipts.
+pub fn block_bloom(receipts: &[Receipt]) -> Bloom {
+    receipts
+        .iter()
+        .fold(Bloom::default(), |mut bloom, receipt| {
+            bloom.accrue_bloom(&receipt.logs_bloom);
+            bloom
+        })
+}
+
+/// Error of the transaction that makes the block invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockError<DBError> {
+    /// Index of the transaction in the block.
+    pub index: usize,
+    pub error: EVMError<DBError>,
+}
+
+/// Check that transaction fits into the gas that is left in the block.
+pub(crate) fn check_block_gas_limit<DBError>(
+    block: &BlockEnv,
+    tx: &TxEnv,
+    cumulative_gas_used: u64,
+) -> Result<(), EVMError<DBError>> {
+    if U256::from(cumulative_gas_used) + U256::from(tx.gas_limit) > block.gas_limit {
+        return Err(EVMError::Transaction(
+            InvalidTransaction::BlockGasLimitReached,
+        ));
+    }
+    Ok(())
+}
+
+/// Add changes of the next transaction to the changes of the block.
+///
+/// Original values of storage slots are kept from the first transaction that loaded them.
+pub(crate) fn merge_state(block_state: &mut State, tx_state: State) {
+    for (address, account) in tx_state {
+        let block_account = match block_state.entry(address) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(account);
+                continue;
+            }
+        };
+        if account.is_destroyed || account.storage_cleared || block_account.is_destroyed {
+            // storage from previous transactions is removed.
+            let storage_cleared = account.storage_cleared || block_account.is_destroyed;
+            let is_touched = account.is_touched || block_account.is_touched;
+            *block_account = Account {
+                storage_cleared,
+                is_touched,
+                ..account
+            };
+            continue;
+        }
+        for (index, slot) in account.storage {
+            match block_account.storage.entry(index) {
+                Entry::Occupied(mut entry) => {
+                    let original_value = entry.get().original_value();
+                    entry.insert(StorageSlot::new_changed(
+                        original_value,
+                        slot.present_value(),
+                    ));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(slot);
+                }
+            }
+        }
+        block_account.info = account.info;
+        block_account.is_touched |= account.is_touched;
+        block_account.is_not_existing = account.is_not_existing;
+    }
+}
+
+/// Number of wei in one gwei, withdrawal amounts are in gwei.
+pub const GWEI_TO_WEI: u64 = 1_000_000_000;
+
+/// Static reward of the block miner, zero after the merge.
+pub fn block_reward(spec_id: SpecId) -> U256 {
+    let ether = U256::from(10).pow(U256::from(18));
+    if SpecId::enabled(spec_id, SpecId::MERGE) {
+        U256::zero()
+    } else if SpecId::enabled(spec_id, SpecId::CONSTANTINOPLE) {
+        ether * 2
+    } else if SpecId::enabled(spec_id, SpecId::BYZANTIUM) {
+        ether * 3
+    } else {
+        ether * 5
+    }
+}
+
+/// Apply rewards of the block miner and of the ommers, given as beneficiary and block number.
+///
+/// Each ommer gets `(8 + ommer_number - block_number) / 8` of the block reward, miner gets
+/// additional `1/32` of the block reward for each included ommer.
+pub fn apply_block_rewards<DB: Database + DatabaseCommit>(
+    db: &mut DB,
+    spec_id: SpecId,
+    block: &BlockEnv,
+    ommers: &[(H160, U256)],
+) -> Result<(), DB::Error> {
+    let reward = block_reward(spec_id);
+    if reward.is_zero() {
+        return Ok(());
+    }
+    let mut balances = Vec::with_capacity(ommers.len() + 1);
+    balances.push((
+        block.coinbase,
+        reward + reward / 32 * U256::from(ommers.len()),
+    ));
+    for (beneficiary, number) in ommers {
+        let ommer_reward = (*number + 8).saturating_sub(block.number) * reward / 8;
+        balances.push((*beneficiary, ommer_reward));
+    }
+    increment_balances(db, balances)
+}
+
+/// Beacon chain withdrawal, EIP-4895.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase"))]
+pub struct Withdrawal {
+    #[cfg_attr(feature = "with-serde", serde(with = "crate::models::serde_hex_u64"))]
+    pub index: u64,
+    #[cfg_attr(feature = "with-serde", serde(with = "crate::models::serde_hex_u64"))]
+    pub validator_index: u64,
+    pub address: H160,
+    /// Amount in gwei.
+    #[cfg_attr(feature = "with-serde", serde(with = "crate::models::serde_hex_u64"))]
+    pub amount: u64,
+}
+
+/// Apply withdrawals of the block, they are applied after all transactions.
+pub fn apply_withdrawals<DB: Database + DatabaseCommit>(
+    db: &mut DB,
+    withdrawals: &[Withdrawal],
+) -> Result<(), DB::Error> {
+    increment_balances(
+        db,
+        withdrawals.iter().map(|withdrawal| {
+            (
+                withdrawal.address,
+                U256::from(withdrawal.amount) * GWEI_TO_WEI,
+            )
+        }),
+    )
+}
+
+/// Irregular state change of the DAO fork, balances of [DAO_DRAIN_LIST] accounts are moved to
+/// [DAO_HARDFORK_BENEFICIARY].
+///
+/// It is applied only at block [DAO_HARDFORK_BLOCK] with [SpecId::DAO_FORK] enabled, before
+/// transactions of the block. For other blocks nothing is changed.
+pub fn apply_dao_fork<DB: Database + DatabaseCommit>(
+    db: &mut DB,
+    spec_id: SpecId,
+    block: &BlockEnv,
+) -> Result<(), DB::Error> {
+    if !SpecId::enabled(spec_id, SpecId::DAO_FORK) || block.number != U256::from(DAO_HARDFORK_BLOCK)
+    {
+        return Ok(());
+    }
+    let mut state = State::new();
+    let mut drained = U256::zero();
+    for address in DAO_DRAIN_LIST {
+        let account = load_account(db, &mut state, address)?;
+        drained += account.info.balance;
+        account.info.balance = U256::zero();
+    }
+    load_account(db, &mut state, DAO_HARDFORK_BENEFICIARY)?
+        .info
+        .balance += drained;
+    db.commit(state);
+    Ok(())
+}
+
+/// Add balances to the accounts and commit them. Zero balances do not touch the account.
+fn increment_balances<DB: Database + DatabaseCommit>(
+    db: &mut DB,
+    balances: impl IntoIterator<Item = (H160, U256)>,
+) -> Result<(), DB::Error> {
+    let mut state = State::new();
+    for (address, balance) in balances {
+        if balance.is_zero() {
+            continue;
+        }
+        load_account(db, &mut state, address)?.info.balance += balance;
+    }
+    db.commit(state);
+    Ok(())
+}
+
+/// Load account into the state and mark it as touched.
+fn load_account<'a, DB: Database>(
+    db: &mut DB,
+    state: &'a mut State,
+    address: H160,
+) -> Result<&'a mut Account, DB::Error> {
+    let account = match state.entry(address) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(db.basic(address)?.unwrap_or_default().into()),
+    };
+    account.is_touched = true;
+    Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{opcode, test_utils::db_with_code, AccountInfo, Database, InMemoryDB, TransactTo};
+    use bytes::Bytes;
+    use primitive_types::{H160, H256};
+
+    #[test]
+    fn bloom_of_log() {
+        let log = Log {
+            address: H160::from_low_u64_be(0xaa),
+            topics: vec![H256::from_low_u64_be(1)],
+            data: Bytes::new(),
+        };
+        let mut bloom = Bloom::default();
+        bloom.accrue_log(&log);
+        assert!(bloom.contains_input(log.address.as_bytes()));
+        assert!(bloom.contains_input(log.topics[0].as_bytes()));
+        assert!(bloom.0.iter().map(|byte| byte.count_ones()).sum::<u32>() <= 6);
+    }
+
+    #[test]
+    fn execute_block() {
+        let caller = H160::from_low_u64_be(0x1000);
+        let contract = H160::from_low_u64_be(0xaa);
+        #[rustfmt::skip]
+        let code = [
+            // SSTORE(0, SLOAD(0) + 1)
+            opcode::PUSH1, 1, opcode::PUSH1, 0, opcode::SLOAD, opcode::ADD,
+            opcode::PUSH1, 0, opcode::SSTORE,
+            // LOG0(0, 0)
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::LOG0,
+            opcode::STOP,
+        ];
+        let db = db_with_code(&[(contract, &code)]);
+
+        let tx = TxEnv {
+            caller,
+            transact_to: TransactTo::Call(contract),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let block = BlockEnv {
+            gas_limit: U256::from(160_000),
+            ..Default::default()
+        };
+
+        let mut evm = crate::new();
+        evm.database(db);
+        let (receipts, state) = evm
+            .transact_block_ref(block.clone(), vec![tx.clone(), tx.clone()])
+            .unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert!(receipts.iter().all(|receipt| receipt.success));
+        assert_eq!(
+            receipts[1].cumulative_gas_used,
+            receipts[0].gas_used + receipts[1].gas_used
+        );
+        // second increment is cheaper as slot is not zero anymore.
+        assert!(receipts[1].gas_used < receipts[0].gas_used);
+        assert!(receipts[0].logs_bloom.contains_input(contract.as_bytes()));
+        assert_eq!(state[&caller].info.nonce, 2);
+        let slot = &state[&contract].storage[&U256::zero()];
+        assert_eq!(
+            (slot.original_value(), slot.present_value()),
+            (U256::zero(), U256::from(2))
+        );
+
+        // gas used by two transactions and gas limit of the third exceed the block gas limit.
+        let error = evm
+            .transact_block_commit(block, vec![tx.clone(), tx.clone(), tx])
+            .unwrap_err();
+        assert_eq!(error.index, 2);
+        assert_eq!(
+            error.error,
+            EVMError::Transaction(InvalidTransaction::BlockGasLimitReached)
+        );
+        assert_eq!(
+            evm.db().unwrap().storage(contract, U256::zero()).unwrap(),
+            U256::from(2)
+        );
+    }
+
+    #[test]
+    fn merge_selfdestruct_after_sstore() {
+        let caller = H160::from_low_u64_be(0x1000);
+        let contract = H160::from_low_u64_be(0xaa);
+        #[rustfmt::skip]
+        let code = [
+            // if calldata is empty jump to SSTORE(1, 5), otherwise SELFDESTRUCT(caller)
+            opcode::CALLDATASIZE, opcode::ISZERO, opcode::PUSH1, 7, opcode::JUMPI,
+            opcode::CALLER, opcode::SELFDESTRUCT,
+            opcode::JUMPDEST, opcode::PUSH1, 5, opcode::PUSH1, 1, opcode::SSTORE,
+            opcode::STOP,
+        ];
+        let mut db = db_with_code(&[(contract, &code)]);
+        db.insert_account_storage(contract, U256::from(2), U256::from(9))
+            .unwrap();
+
+        let tx = TxEnv {
+            caller,
+            transact_to: TransactTo::Call(contract),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let selfdestruct = TxEnv {
+            data: Bytes::from_static(&[1]),
+            ..tx.clone()
+        };
+
+        let mut evm = crate::new();
+        evm.env.cfg.spec_id = SpecId::LONDON;
+        evm.database(db.clone());
+        let (receipts, state) = evm
+            .transact_block_ref(BlockEnv::default(), vec![tx, selfdestruct])
+            .unwrap();
+        assert!(receipts.iter().all(|receipt| receipt.success));
+        assert!(state[&contract].is_destroyed);
+        assert!(state[&contract].storage.is_empty());
+
+        db.commit(state);
+        assert_eq!(db.basic(contract).unwrap(), None);
+        assert_eq!(db.storage(contract, U256::one()).unwrap(), U256::zero());
+        assert_eq!(db.storage(contract, U256::from(2)).unwrap(), U256::zero());
+    }
+
+    #[test]
+    fn merge_recreated_account() {
+        let address = H160::from_low_u64_be(0xaa);
+        let account = |storage: &[(u64, u64, u64)]| Account {
+            info: AccountInfo::from_balance(U256::one()),
+            storage: storage
+                .iter()
+                .map(|(index, original, present)| {
+                    (
+                        U256::from(*index),
+                        StorageSlot::new_changed(U256::from(*original), U256::from(*present)),
+                    )
+                })
+                .collect(),
+            storage_cleared: false,
+            is_destroyed: false,
+            is_touched: true,
+            is_not_existing: false,
+        };
+
+        let mut block_state = State::new();
+        merge_state(
+            &mut block_state,
+            State::from([(address, account(&[(1, 0, 5), (2, 9, 9)]))]),
+        );
+        merge_state(
+            &mut block_state,
+            State::from([(address, account(&[(1, 5, 6)]))]),
+        );
+        let slot = &block_state[&address].storage[&U256::one()];
+        assert_eq!(
+            (slot.original_value(), slot.present_value()),
+            (U256::zero(), U256::from(6))
+        );
+
+        let destroyed = Account {
+            is_destroyed: true,
+            ..account(&[])
+        };
+        merge_state(&mut block_state, State::from([(address, destroyed)]));
+        // account created again at the same address does not see storage from before.
+        merge_state(
+            &mut block_state,
+            State::from([(address, account(&[(3, 0, 7)]))]),
+        );
+        let merged = &block_state[&address];
+        assert!(merged.storage_cleared);
+        assert!(!merged.is_destroyed);
+        assert_eq!(merged.storage.keys().collect::<Vec<_>>(), [&U256::from(3)]);
+
+        // storage cleared by a later transaction drops slots of previous transactions.
+        let cleared = Account {
+            storage_cleared: true,
+            ..account(&[(4, 0, 1)])
+        };
+        merge_state(&mut block_state, State::from([(address, cleared)]));
+        let merged = &block_state[&address];
+        assert!(merged.storage_cleared);
+        assert_eq!(merged.storage.keys().collect::<Vec<_>>(), [&U256::from(4)]);
+    }
+
+    #[test]
+    fn rewards_and_withdrawals() {
+        let miner = H160::from_low_u64_be(1);
+        let ommer = H160::from_low_u64_be(2);
+        let mut db = InMemoryDB::default();
+        let block = BlockEnv {
+            number: U256::from(100),
+            coinbase: miner,
+            ..Default::default()
+        };
+        let balance = |db: &mut InMemoryDB, address| db.basic(address).unwrap().unwrap().balance;
+
+        apply_block_rewards(&mut db, SpecId::LONDON, &block, &[(ommer, U256::from(99))]).unwrap();
+        let reward = block_reward(SpecId::LONDON);
+        assert_eq!(balance(&mut db, miner), reward + reward / 32);
+        assert_eq!(balance(&mut db, ommer), reward * 7 / 8);
+
+        apply_block_rewards(&mut db, SpecId::MERGE, &block, &[]).unwrap();
+        apply_withdrawals(
+            &mut db,
+            &[Withdrawal {
+                address: miner,
+                amount: 3,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        assert_eq!(
+            balance(&mut db, miner),
+            reward + reward / 32 + U256::from(3 * GWEI_TO_WEI)
+        );
+    }
+
+    #[test]
+    fn dao_fork_drains_accounts() {
+        // the DAO and the last extra balance account from geth list.
+        let dao =
+            H160::from_slice(&hex::decode("bb9bc244d798123fde783fcc1c72d3bb8c189413").unwrap());
+        let extra_balance =
+            H160::from_slice(&hex::decode("807640a13483f8ac783c557fcdf27be11ea4ac7a").unwrap());
+        assert_eq!(DAO_DRAIN_LIST[114..], [dao, extra_balance]);
+        let unique: alloc::collections::BTreeSet<_> = DAO_DRAIN_LIST.iter().collect();
+        assert_eq!(unique.len(), DAO_DRAIN_LIST.len());
+        assert!(!unique.contains(&DAO_HARDFORK_BENEFICIARY));
+
+        let mut db = InMemoryDB::default();
+        for (i, address) in DAO_DRAIN_LIST.iter().enumerate() {
+            db.insert_account_info(*address, AccountInfo::from_balance(U256::from(i + 1)));
+        }
+        let balance = |db: &mut InMemoryDB, address| {
+            db.basic(address)
+                .unwrap()
+                .map(|info| info.balance)
+                .unwrap_or_default()
+        };
+        let fork_block = BlockEnv {
+            number: U256::from(DAO_HARDFORK_BLOCK),
+            ..Default::default()
+        };
+
+        // nothing happens before the fork or at other blocks.
+        apply_dao_fork(&mut db, SpecId::HOMESTEAD, &fork_block).unwrap();
+        let next_block = BlockEnv {
+            number: U256::from(DAO_HARDFORK_BLOCK + 1),
+            ..Default::default()
+        };
+        apply_dao_fork(&mut db, SpecId::DAO_FORK, &next_block).unwrap();
+        assert_eq!(balance(&mut db, dao), U256::from(115));
+        assert_eq!(balance(&mut db, DAO_HARDFORK_BENEFICIARY), U256::zero());
+
+        apply_dao_fork(&mut db, SpecId::DAO_FORK, &fork_block).unwrap();
+        assert!(DAO_DRAIN_LIST
+            .iter()
+            .all(|address| balance(&mut db, *address).is_zero()));
+        assert_eq!(
+            balance(&mut db, DAO_HARDFORK_BENEFICIARY),
+            U256::from(116 * 117 / 2)
+        );
+    }
+}
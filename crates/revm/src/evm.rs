@@ -1,12 +1,18 @@
 use crate::{
-    db::{Database, DatabaseCommit, DatabaseRef, RefDBWrapper},
+    db::{CacheDB, Database, DatabaseCommit, DatabaseRef, RefDBWrapper},
     evm_impl::{EVMImpl, Transact},
     journaled_state::State,
-    specification, Env, ExecutionResult, Inspector, NoOpInspector,
+    precompile::{StatefulPrecompile, StatefulPrecompiles},
+    specification, AccessListInspector, BlockEnv, BlockError, EVMError, Env, EstimateGasResult,
+    ExecutionResult, Halt, Inspector, InvalidTransaction, NoOpInspector, Receipt, TxEnv,
 };
-use alloc::boxed::Box;
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use primitive_types::{H160, U256};
 use revm_precompiles::Precompiles;
 
+/// Maximal number of executions done by [EVM::create_access_list].
+pub const ACCESS_LIST_MAX_ITERATIONS: usize = 10;
+
 /// Struct that takes Database and enabled transact to update state directly to database.
 /// additionally it allows user to set all environment parameters.
 ///
@@ -28,6 +34,10 @@ use revm_precompiles::Precompiles;
 pub struct EVM<DB> {
     pub env: Env,
     pub db: Option<DB>,
+    /// Precompiles used instead of the ones defined by `env.cfg.spec_id`.
+    pub precompiles: Option<Precompiles>,
+    /// Precompiles with access to the state. They take precedence over standard precompiles.
+    pub stateful_precompiles: StatefulPrecompiles,
 }
 
 pub fn new<DB>() -> EVM<DB> {
@@ -42,25 +52,60 @@ impl<DB> Default for EVM<DB> {
 
 impl<DB: Database + DatabaseCommit> EVM<DB> {
     /// Execute transaction and apply result to database
-    pub fn transact_commit(&mut self) -> ExecutionResult {
-        let (exec_result, state) = self.transact();
+    pub fn transact_commit(&mut self) -> Result<ExecutionResult, EVMError<DB::Error>> {
+        let (exec_result, state) = self.transact()?;
         self.db.as_mut().unwrap().commit(state);
-        exec_result
+        Ok(exec_result)
     }
     /// Inspect transaction and commit changes to database.
-    pub fn inspect_commit<INSP: Inspector<DB>>(&mut self, inspector: INSP) -> ExecutionResult {
-        let (exec_result, state) = self.inspect(inspector);
+    pub fn inspect_commit<INSP: Inspector<DB>>(
+        &mut self,
+        inspector: INSP,
+    ) -> Result<ExecutionResult, EVMError<DB::Error>> {
+        let (exec_result, state) = self.inspect(inspector)?;
         self.db.as_mut().unwrap().commit(state);
-        exec_result
+        Ok(exec_result)
+    }
+
+    /// Execute transactions of the block and commit each of them to database.
+    ///
+    /// `env.block` is set to `block`. If transaction is invalid or it does not fit into the block
+    /// gas limit, execution stops and transactions before it stay committed.
+    pub fn transact_block_commit(
+        &mut self,
+        block: BlockEnv,
+        txs: impl IntoIterator<Item = TxEnv>,
+    ) -> Result<Vec<Receipt>, BlockError<DB::Error>> {
+        self.env.block = block;
+        let mut receipts = Vec::new();
+        let mut cumulative_gas_used = 0;
+        for (index, tx) in txs.into_iter().enumerate() {
+            crate::block::check_block_gas_limit(&self.env.block, &tx, cumulative_gas_used)
+                .map_err(|error| BlockError { index, error })?;
+            self.env.tx = tx;
+            let result = self
+                .transact_commit()
+                .map_err(|error| BlockError { index, error })?;
+            cumulative_gas_used += result.gas_used();
+            receipts.push(Receipt::new(&result, cumulative_gas_used));
+        }
+        Ok(receipts)
     }
 }
 
 impl<DB: Database> EVM<DB> {
     /// Execute transaction without writing to DB, return change state.
-    pub fn transact(&mut self) -> (ExecutionResult, State) {
+    pub fn transact(&mut self) -> Result<(ExecutionResult, State), EVMError<DB::Error>> {
         if let Some(db) = self.db.as_mut() {
             let mut noop = NoOpInspector {};
-            let out = evm_inner::<DB, false>(&mut self.env, db, &mut noop).transact();
+            let out = evm_inner::<DB, false>(
+                &mut self.env,
+                db,
+                &mut noop,
+                self.precompiles.as_ref(),
+                &self.stateful_precompiles,
+            )
+            .transact();
             out
         } else {
             panic!("Database needs to be set");
@@ -71,9 +116,16 @@ impl<DB: Database> EVM<DB> {
     pub fn inspect<INSP: Inspector<DB>>(
         &mut self,
         mut inspector: INSP,
-    ) -> (ExecutionResult, State) {
+    ) -> Result<(ExecutionResult, State), EVMError<DB::Error>> {
         if let Some(db) = self.db.as_mut() {
-            evm_inner::<DB, true>(&mut self.env, db, &mut inspector).transact()
+            evm_inner::<DB, true>(
+                &mut self.env,
+                db,
+                &mut inspector,
+                self.precompiles.as_ref(),
+                &self.stateful_precompiles,
+            )
+            .transact()
         } else {
             panic!("Database needs to be set");
         }
@@ -82,14 +134,19 @@ impl<DB: Database> EVM<DB> {
 
 impl<'a, DB: DatabaseRef> EVM<DB> {
     /// Execute transaction without writing to DB, return change state.
-    pub fn transact_ref(&self) -> (ExecutionResult, State) {
+    pub fn transact_ref(&self) -> Result<(ExecutionResult, State), EVMError<DB::Error>> {
         if let Some(db) = self.db.as_ref() {
             let mut noop = NoOpInspector {};
             let mut db = RefDBWrapper::new(db);
             let db = &mut db;
-            let out =
-                evm_inner::<RefDBWrapper<DB::Error>, false>(&mut self.env.clone(), db, &mut noop)
-                    .transact();
+            let out = evm_inner::<RefDBWrapper<DB::Error>, false>(
+                &mut self.env.clone(),
+                db,
+                &mut noop,
+                self.precompiles.as_ref(),
+                &self.stateful_precompiles,
+            )
+            .transact();
             out
         } else {
             panic!("Database needs to be set");
@@ -100,7 +157,7 @@ impl<'a, DB: DatabaseRef> EVM<DB> {
     pub fn inspect_ref<INSP: Inspector<RefDBWrapper<'a, DB::Error>>>(
         &'a self,
         mut inspector: INSP,
-    ) -> (ExecutionResult, State) {
+    ) -> Result<(ExecutionResult, State), EVMError<DB::Error>> {
         if let Some(db) = self.db.as_ref() {
             let mut db = RefDBWrapper::new(db);
             let db = &mut db;
@@ -108,6 +165,8 @@ impl<'a, DB: DatabaseRef> EVM<DB> {
                 &mut self.env.clone(),
                 db,
                 &mut inspector,
+                self.precompiles.as_ref(),
+                &self.stateful_precompiles,
             )
             .transact();
             out
@@ -117,14 +176,180 @@ impl<'a, DB: DatabaseRef> EVM<DB> {
     }
 }
 
+impl<DB: DatabaseRef> EVM<DB> {
+    /// Find minimal `env.tx.gas_limit` with which transaction succeeds, same as `eth_estimateGas`.
+    ///
+    /// Highest gas limit is `env.tx.gas_limit` capped by block gas limit and by the gas caller can
+    /// pay for. Transaction is first executed with the highest gas limit and if it fails, or the
+    /// limit does not cover intrinsic gas, [EstimateGasResult::Failure] is returned. Otherwise
+    /// binary search is done between gas used by that execution and the highest gas limit.
+    /// If caller can't pay for transferred value, [InvalidTransaction::LackOfFundForValue] is
+    /// returned. Database is not changed, all executions share one [CacheDB] so state is loaded only once.
+    pub fn estimate_gas(&self) -> Result<EstimateGasResult, EVMError<DB::Error>> {
+        let db = self.db.as_ref().expect("Database needs to be set");
+        let mut db = CacheDB::new(db);
+        let mut env = self.env.clone();
+
+        let mut hi = env.tx.gas_limit;
+        if env.block.gas_limit < U256::from(hi) {
+            hi = env.block.gas_limit.as_u64();
+        }
+        let balance = Database::basic(&mut db, env.tx.caller)
+            .map_err(EVMError::Database)?
+            .map(|acc| acc.balance)
+            .unwrap_or_default();
+        if env.tx.value > balance {
+            return Err(EVMError::Transaction(
+                InvalidTransaction::LackOfFundForValue,
+            ));
+        }
+        // cap gas limit to the balance that is left after value transfer.
+        let gas_price = env.effective_gas_price();
+        if !gas_price.is_zero() {
+            let allowance = (balance - env.tx.value) / gas_price;
+            if allowance < U256::from(hi) {
+                hi = allowance.as_u64();
+            }
+        }
+
+        let mut transact = |gas_limit: u64| {
+            env.tx.gas_limit = gas_limit;
+            evm_inner::<CacheDB<&DB>, false>(
+                &mut env,
+                &mut db,
+                &mut NoOpInspector {},
+                self.precompiles.as_ref(),
+                &self.stateful_precompiles,
+            )
+            .transact()
+            .map(|(result, _)| result)
+        };
+
+        let gas_used = match transact(hi) {
+            Ok(ExecutionResult::Success { gas_used, .. }) => gas_used,
+            Ok(result) => return Ok(EstimateGasResult::Failure(result)),
+            Err(EVMError::Transaction(InvalidTransaction::CallGasCostMoreThenGasLimit)) => {
+                return Ok(EstimateGasResult::Failure(ExecutionResult::Halt {
+                    reason: Halt::OutOfGas,
+                    gas_used: hi,
+                }));
+            }
+            Err(err) => return Err(err),
+        };
+        // transaction can't succeed with less gas then it used, but because of refunds and
+        // 63/64 rule for calls it can need more.
+        let mut lo = gas_used.saturating_sub(1);
+        while lo + 1 < hi {
+            let mid = lo + (hi - lo) / 2;
+            match transact(mid) {
+                Ok(ExecutionResult::Success { .. }) => hi = mid,
+                Ok(_) | Err(EVMError::Transaction(_)) => lo = mid,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(EstimateGasResult::Success { gas_limit: hi })
+    }
+
+    /// Create EIP-2930 access list of the transaction, same as `eth_createAccessList`.
+    ///
+    /// Transaction is executed with the access list found in the previous execution until the
+    /// access list stops changing or [ACCESS_LIST_MAX_ITERATIONS] executions are done. Returned
+    /// result is of the last execution, together with the access list it was executed with.
+    /// Database is not changed.
+    #[allow(clippy::type_complexity)]
+    pub fn create_access_list(
+        &self,
+    ) -> Result<(ExecutionResult, Vec<(H160, Vec<U256>)>), EVMError<DB::Error>> {
+        let db = self.db.as_ref().expect("Database needs to be set");
+        let mut db = CacheDB::new(db);
+        let mut env = self.env.clone();
+        let mut iterations = 0;
+        loop {
+            iterations += 1;
+            let mut inspector = AccessListInspector::new();
+            let (result, _) = evm_inner::<CacheDB<&DB>, true>(
+                &mut env,
+                &mut db,
+                &mut inspector,
+                self.precompiles.as_ref(),
+                &self.stateful_precompiles,
+            )
+            .transact()?;
+            let access_list = inspector.access_list();
+            // accessed accounts can depend on gas, in that case the list never stops changing.
+            if access_list == env.tx.access_list || iterations == ACCESS_LIST_MAX_ITERATIONS {
+                return Ok((result, env.tx.access_list));
+            }
+            env.tx.access_list = access_list;
+        }
+    }
+
+    /// Execute transactions of the block without writing to DB, return receipts and changes of
+    /// all transactions merged into one state.
+    ///
+    /// `env.block` is not changed, `block` is used for execution.
+    pub fn transact_block_ref(
+        &self,
+        block: BlockEnv,
+        txs: impl IntoIterator<Item = TxEnv>,
+    ) -> Result<(Vec<Receipt>, State), BlockError<DB::Error>> {
+        let db = self.db.as_ref().expect("Database needs to be set");
+        let mut db = CacheDB::new(db);
+        let mut env = self.env.clone();
+        env.block = block;
+        let mut receipts = Vec::new();
+        let mut block_state = State::new();
+        let mut cumulative_gas_used = 0;
+        for (index, tx) in txs.into_iter().enumerate() {
+            crate::block::check_block_gas_limit(&env.block, &tx, cumulative_gas_used)
+                .map_err(|error| BlockError { index, error })?;
+            env.tx = tx;
+            let (result, state) = evm_inner::<CacheDB<&DB>, false>(
+                &mut env,
+                &mut db,
+                &mut NoOpInspector {},
+                self.precompiles.as_ref(),
+                &self.stateful_precompiles,
+            )
+            .transact()
+            .map_err(|error| BlockError { index, error })?;
+            db.commit(state.clone());
+            crate::block::merge_state(&mut block_state, state);
+            cumulative_gas_used += result.gas_used();
+            receipts.push(Receipt::new(&result, cumulative_gas_used));
+        }
+        Ok((receipts, block_state))
+    }
+}
+
 impl<DB> EVM<DB> {
     pub fn new() -> Self {
         Self {
             env: Env::default(),
             db: None,
+            precompiles: None,
+            stateful_precompiles: StatefulPrecompiles::default(),
         }
     }
 
+    /// Set precompiles that are going to be used instead of the fork ones.
+    ///
+    /// Fork precompiles can be obtained with `Precompiles::new(SpecId::to_precompile_id(spec_id))`
+    /// and extended or changed before they are set.
+    pub fn precompiles(&mut self, precompiles: Precompiles) {
+        self.precompiles = Some(precompiles);
+    }
+
+    /// Register stateful precompile at given address. Replaces previous one if present.
+    pub fn stateful_precompile<P: StatefulPrecompile + 'static>(
+        &mut self,
+        address: H160,
+        precompile: P,
+    ) {
+        self.stateful_precompiles
+            .insert(address, Arc::new(precompile));
+    }
+
     pub fn database(&mut self, db: DB) {
         self.db = Some(db);
     }
@@ -139,13 +364,16 @@ impl<DB> EVM<DB> {
 }
 
 macro_rules! create_evm {
-    ($spec:ident, $db:ident,$env:ident,$inspector:ident) => {
+    ($spec:ident, $db:ident,$env:ident,$inspector:ident,$precompiles:ident,$stateful:ident) => {
         Box::new(EVMImpl::<'a, $spec, DB, INSPECT>::new(
             $db,
             $env,
             $inspector,
-            Precompiles::new(SpecId::to_precompile_id($spec::SPEC_ID)).clone(),
-        )) as Box<dyn Transact + 'a>
+            $precompiles.cloned().unwrap_or_else(|| {
+                Precompiles::new(SpecId::to_precompile_id($spec::SPEC_ID)).clone()
+            }),
+            $stateful,
+        )) as Box<dyn Transact<DB::Error> + 'a>
     };
 }
 
@@ -153,21 +381,210 @@ pub fn evm_inner<'a, DB: Database, const INSPECT: bool>(
     env: &'a mut Env,
     db: &'a mut DB,
     insp: &'a mut dyn Inspector<DB>,
-) -> Box<dyn Transact + 'a> {
+    precompiles: Option<&'a Precompiles>,
+    stateful: &'a StatefulPrecompiles,
+) -> Box<dyn Transact<DB::Error> + 'a> {
     use specification::*;
     match env.cfg.spec_id {
-        SpecId::FRONTIER | SpecId::FRONTIER_THAWING => create_evm!(FrontierSpec, db, env, insp),
-        SpecId::HOMESTEAD | SpecId::DAO_FORK => create_evm!(HomesteadSpec, db, env, insp),
-        SpecId::TANGERINE => create_evm!(TangerineSpec, db, env, insp),
-        SpecId::SPURIOUS_DRAGON => create_evm!(SpuriousDragonSpec, db, env, insp),
-        SpecId::BYZANTIUM => create_evm!(ByzantiumSpec, db, env, insp),
-        SpecId::PETERSBURG | SpecId::CONSTANTINOPLE => create_evm!(PetersburgSpec, db, env, insp),
-        SpecId::ISTANBUL | SpecId::MUIR_GLACIER => create_evm!(IstanbulSpec, db, env, insp),
-        SpecId::BERLIN => create_evm!(BerlinSpec, db, env, insp),
+        SpecId::FRONTIER | SpecId::FRONTIER_THAWING => {
+            create_evm!(FrontierSpec, db, env, insp, precompiles, stateful)
+        }
+        SpecId::HOMESTEAD | SpecId::DAO_FORK => {
+            create_evm!(HomesteadSpec, db, env, insp, precompiles, stateful)
+        }
+        SpecId::TANGERINE => create_evm!(TangerineSpec, db, env, insp, precompiles, stateful),
+        SpecId::SPURIOUS_DRAGON => {
+            create_evm!(SpuriousDragonSpec, db, env, insp, precompiles, stateful)
+        }
+        SpecId::BYZANTIUM => create_evm!(ByzantiumSpec, db, env, insp, precompiles, stateful),
+        SpecId::PETERSBURG | SpecId::CONSTANTINOPLE => {
+            create_evm!(PetersburgSpec, db, env, insp, precompiles, stateful)
+        }
+        SpecId::ISTANBUL | SpecId::MUIR_GLACIER => {
+            create_evm!(IstanbulSpec, db, env, insp, precompiles, stateful)
+        }
+        SpecId::BERLIN => create_evm!(BerlinSpec, db, env, insp, precompiles, stateful),
         SpecId::LONDON | SpecId::ARROW_GLACIER | SpecId::GRAY_GLACIER => {
-            create_evm!(LondonSpec, db, env, insp)
+            create_evm!(LondonSpec, db, env, insp, precompiles, stateful)
+        }
+        SpecId::MERGE => create_evm!(MergeSpec, db, env, insp, precompiles, stateful),
+        SpecId::SHANGHAI => create_evm!(ShanghaiSpec, db, env, insp, precompiles, stateful),
+        SpecId::CANCUN => create_evm!(CancunSpec, db, env, insp, precompiles, stateful),
+        SpecId::LATEST => create_evm!(LatestSpec, db, env, insp, precompiles, stateful),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{opcode, test_utils::db_with_code, AccountInfo, Bytecode, InMemoryDB, TransactTo};
+
+    #[test]
+    fn estimate_gas_of_nested_call() {
+        let a = H160::from_low_u64_be(0xaa);
+        let b = H160::from_low_u64_be(0xbb);
+        #[rustfmt::skip]
+        let code_a = [
+            // CALL(gas, 0xbb, 0, 0, 0, 0, 0)
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0,
+            opcode::PUSH1, 0, opcode::PUSH1, 0xbb, opcode::GAS, opcode::CALL,
+            // revert if call failed
+            opcode::PUSH1, 22, opcode::JUMPI,
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::REVERT,
+            opcode::JUMPDEST, opcode::STOP,
+        ];
+        // SSTORE(0, 1)
+        let code_b = [
+            opcode::PUSH1,
+            1,
+            opcode::PUSH1,
+            0,
+            opcode::SSTORE,
+            opcode::STOP,
+        ];
+
+        let mut evm = crate::new();
+        evm.database(db_with_code(&[(a, &code_a), (b, &code_b)]));
+        evm.env.tx.transact_to = TransactTo::Call(a);
+        evm.env.tx.gas_limit = 1_000_000;
+
+        let gas_limit = match evm.estimate_gas().unwrap() {
+            EstimateGasResult::Success { gas_limit } => gas_limit,
+            result => panic!("unexpected result {:?}", result),
+        };
+
+        evm.env.tx.gas_limit = gas_limit;
+        let (result, _) = evm.transact_ref().unwrap();
+        assert!(result.is_success());
+        // 63/64 rule makes estimation higher then gas used.
+        assert!(result.gas_used() < gas_limit);
+
+        evm.env.tx.gas_limit = gas_limit - 1;
+        let (result, _) = evm.transact_ref().unwrap();
+        assert!(!result.is_success());
+    }
+
+    #[test]
+    fn estimate_gas_of_reverting_call() {
+        let a = H160::from_low_u64_be(0xaa);
+        let code = [opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::REVERT];
+
+        let mut evm = crate::new();
+        evm.database(db_with_code(&[(a, &code)]));
+        evm.env.tx.transact_to = TransactTo::Call(a);
+
+        match evm.estimate_gas().unwrap() {
+            EstimateGasResult::Failure(ExecutionResult::Revert { .. }) => (),
+            result => panic!("unexpected result {:?}", result),
         }
-        SpecId::MERGE => create_evm!(MergeSpec, db, env, insp),
-        SpecId::LATEST => create_evm!(LatestSpec, db, env, insp),
+    }
+
+    #[test]
+    fn create_access_list_is_capped() {
+        let a = H160::from_low_u64_be(0xaa);
+        // BALANCE(GAS), accessed account changes with every new access list.
+        let code = [opcode::GAS, opcode::BALANCE, opcode::POP, opcode::STOP];
+
+        let mut evm = crate::new();
+        evm.database(db_with_code(&[(a, &code)]));
+        evm.env.tx.transact_to = TransactTo::Call(a);
+        evm.env.tx.gas_limit = 100_000;
+
+        let (result, access_list) = evm.create_access_list().unwrap();
+        assert!(result.is_success());
+        assert_eq!(access_list.len(), ACCESS_LIST_MAX_ITERATIONS - 1);
+
+        evm.env.tx.access_list = access_list;
+        let (expected, _) = evm.transact_ref().unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn estimate_gas_value_more_than_balance() {
+        let a = H160::from_low_u64_be(0xaa);
+        let caller = H160::from_low_u64_be(0x1000);
+        let mut db = db_with_code(&[(a, &[opcode::STOP])]);
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(1_000_000)));
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.caller = caller;
+        evm.env.tx.transact_to = TransactTo::Call(a);
+        evm.env.tx.gas_price = U256::one();
+        evm.env.tx.value = U256::from(1_000_001);
+
+        assert_eq!(
+            evm.estimate_gas().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::LackOfFundForValue)
+        );
+
+        // value that leaves enough balance for intrinsic gas is fine.
+        evm.env.tx.value = U256::from(1_000_000 - 21_000);
+        assert_eq!(
+            evm.estimate_gas().unwrap(),
+            EstimateGasResult::Success { gas_limit: 21_000 }
+        );
+    }
+
+    #[test]
+    fn estimate_gas_below_intrinsic_gas() {
+        let a = H160::from_low_u64_be(0xaa);
+
+        let mut evm = crate::new();
+        evm.database(db_with_code(&[(a, &[opcode::STOP])]));
+        evm.env.tx.transact_to = TransactTo::Call(a);
+        evm.env.tx.gas_limit = 20_000;
+
+        assert_eq!(
+            evm.estimate_gas().unwrap(),
+            EstimateGasResult::Failure(ExecutionResult::Halt {
+                reason: Halt::OutOfGas,
+                gas_used: 20_000,
+            })
+        );
+
+        // estimation of a plain call is exactly intrinsic gas.
+        evm.env.tx.gas_limit = 100_000;
+        assert_eq!(
+            evm.estimate_gas().unwrap(),
+            EstimateGasResult::Success { gas_limit: 21_000 }
+        );
+    }
+
+    /// Database that fails to load storage.
+    struct StorageErrorDB(InMemoryDB);
+
+    impl DatabaseRef for StorageErrorDB {
+        type Error = &'static str;
+
+        fn basic(&self, address: H160) -> Result<Option<AccountInfo>, Self::Error> {
+            Ok(self.0.basic(address).unwrap())
+        }
+
+        fn code_by_hash(&self, code_hash: primitive_types::H256) -> Result<Bytecode, Self::Error> {
+            Ok(self.0.code_by_hash(code_hash).unwrap())
+        }
+
+        fn storage(&self, _address: H160, _index: U256) -> Result<U256, Self::Error> {
+            Err("storage unavailable")
+        }
+
+        fn block_hash(&self, number: U256) -> Result<primitive_types::H256, Self::Error> {
+            Ok(self.0.block_hash(number).unwrap())
+        }
+    }
+
+    #[test]
+    fn database_error_of_sload() {
+        let contract = H160::from_low_u64_be(0xaa);
+        let code = [opcode::PUSH1, 0, opcode::SLOAD, opcode::STOP];
+        let mut evm = crate::new();
+        evm.database(StorageErrorDB(db_with_code(&[(contract, &code)])));
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        evm.env.tx.gas_limit = 100_000;
+        assert_eq!(
+            evm.transact_ref().unwrap_err(),
+            EVMError::Database("storage unavailable")
+        );
     }
 }
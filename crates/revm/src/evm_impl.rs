@@ -1,21 +1,24 @@
 use crate::{
     db::Database,
     gas,
+    instructions::SuccessOrHalt,
     interpreter::{self, bytecode::Bytecode},
     interpreter::{Contract, Interpreter},
     journaled_state::{Account, JournaledState, State},
     models::SelfDestructResult,
+    precompile::StatefulPrecompiles,
     return_ok, return_revert, AnalysisKind, CallContext, CallInputs, CallScheme, CreateInputs,
-    CreateScheme, Env, ExecutionResult, Gas, Inspector, Log, Return, Spec,
+    CreateScheme, EVMError, Env, ExecutionResult, Gas, Inspector, InvalidTransaction, Log, Return,
+    Spec,
     SpecId::{self, *},
     TransactOut, TransactTo, Transfer, KECCAK_EMPTY,
 };
 use alloc::vec::Vec;
 use bytes::Bytes;
-use core::{cmp::min, marker::PhantomData};
+use core::{cmp::Ordering, marker::PhantomData};
 use hashbrown::HashMap as Map;
 use primitive_types::{H160, H256, U256};
-use revm_precompiles::{Precompile, PrecompileOutput, Precompiles};
+use revm_precompiles::{Precompile, PrecompileOutput, PrecompileResult, Precompiles};
 use sha3::{Digest, Keccak256};
 
 pub struct EVMData<'a, DB: Database> {
@@ -28,31 +31,33 @@ pub struct EVMData<'a, DB: Database> {
 pub struct EVMImpl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> {
     data: EVMData<'a, DB>,
     precompiles: Precompiles,
+    stateful_precompiles: &'a StatefulPrecompiles,
     inspector: &'a mut dyn Inspector<DB>,
     _phantomdata: PhantomData<GSPEC>,
 }
 
-pub trait Transact {
+pub trait Transact<DBError> {
     /// Do transaction.
     /// Return Return, Output for call or Address if we are creating contract, gas spend, gas refunded, State that needs to be applied.
-    fn transact(&mut self) -> (ExecutionResult, State);
+    /// If database returned error it is going to be returned as [EVMError::Database].
+    fn transact(&mut self) -> Result<(ExecutionResult, State), EVMError<DBError>>;
 }
 
-impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact
+impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact<DB::Error>
     for EVMImpl<'a, GSPEC, DB, INSPECT>
 {
-    fn transact(&mut self) -> (ExecutionResult, State) {
+    fn transact(&mut self) -> Result<(ExecutionResult, State), EVMError<DB::Error>> {
         let caller = self.data.env.tx.caller;
         let value = self.data.env.tx.value;
         let data = self.data.env.tx.data.clone();
         let gas_limit = self.data.env.tx.gas_limit;
-        let exit = |reason: Return| (ExecutionResult::new_with_reason(reason), State::new());
+        let invalid = |reason: InvalidTransaction| Err(EVMError::Transaction(reason));
 
         if GSPEC::enabled(LONDON) {
             if let Some(priority_fee) = self.data.env.tx.gas_priority_fee {
                 if priority_fee > self.data.env.tx.gas_price {
                     // or gas_max_fee for eip1559
-                    return exit(Return::GasMaxFeeGreaterThanPriorityFee);
+                    return invalid(InvalidTransaction::GasMaxFeeGreaterThanPriorityFee);
                 }
             }
             let effective_gas_price = self.data.env.effective_gas_price();
@@ -62,36 +67,73 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact
             // TODO maybe do this checks when creating evm. We already have all data there
             // or should be move effective_gas_price inside transact fn
             if effective_gas_price < basefee {
-                return exit(Return::GasPriceLessThenBasefee);
+                return invalid(InvalidTransaction::GasPriceLessThenBasefee);
             }
             // check if priority fee is lower then max fee
         }
         // unusual to be found here, but check if gas_limit is more then block_gas_limit
         if U256::from(gas_limit) > self.data.env.block.gas_limit {
-            return exit(Return::CallerGasLimitMoreThenBlock);
+            return invalid(InvalidTransaction::CallerGasLimitMoreThenBlock);
+        }
+
+        let check_nonce_and_chain_id = !self.data.env.cfg.disable_nonce_and_chain_id_check;
+        // check if transaction is signed for this chain. EIP-155: Simple replay attack protection
+        if check_nonce_and_chain_id {
+            if let Some(chain_id) = self.data.env.tx.chain_id {
+                if U256::from(chain_id) != self.data.env.cfg.chain_id {
+                    return invalid(InvalidTransaction::InvalidChainId);
+                }
+            }
+        }
+
+        // EIP-3860: Limit and meter initcode
+        if GSPEC::enabled(SHANGHAI) && matches!(self.data.env.tx.transact_to, TransactTo::Create(_))
+        {
+            let max_initcode_size = self
+                .data
+                .env
+                .cfg
+                .limit_contract_code_size
+                .map(|limit| limit.saturating_mul(2))
+                .unwrap_or(interpreter::MAX_INITCODE_SIZE);
+            if self.data.env.tx.data.len() > max_initcode_size {
+                return invalid(InvalidTransaction::CreateInitcodeSizeLimit);
+            }
         }
 
         let mut gas = Gas::new(gas_limit);
         // record initial gas cost. if not using gas metering init will return 0
-        if !gas.record_cost(self.initialization::<GSPEC>()) {
-            return exit(Return::OutOfGas);
+        if !gas.record_cost(self.initialization::<GSPEC>()?) {
+            return invalid(InvalidTransaction::CallGasCostMoreThenGasLimit);
         }
 
         // load acc
-        if self
-            .data
+        self.data
             .journaled_state
             .load_account(caller, self.data.db)
-            .is_err()
-        {
-            return exit(Return::FatalExternalError);
-        }
+            .map_err(EVMError::Database)?;
 
         // EIP-3607: Reject transactions from senders with deployed code
         // This EIP is introduced after london but there was no colision in past
         // so we can leave it enabled always
         if self.data.journaled_state.account(caller).info.code_hash != KECCAK_EMPTY {
-            return exit(Return::RejectCallerWithCode);
+            return invalid(InvalidTransaction::RejectCallerWithCode);
+        }
+
+        // check nonce of transaction against nonce of caller account.
+        if check_nonce_and_chain_id {
+            if let Some(tx_nonce) = self.data.env.tx.nonce {
+                let state_nonce = self.data.journaled_state.account(caller).info.nonce;
+                // EIP-2681: Limit account nonce to 2^64-1
+                if state_nonce == u64::MAX {
+                    return invalid(InvalidTransaction::NonceOverflowInTransaction);
+                }
+                match tx_nonce.cmp(&state_nonce) {
+                    Ordering::Greater => return invalid(InvalidTransaction::NonceTooHigh),
+                    Ordering::Less => return invalid(InvalidTransaction::NonceTooLow),
+                    Ordering::Equal => (),
+                }
+            }
         }
 
         // substract gas_limit*gas_price from current account.
@@ -107,17 +149,17 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact
                 .info
                 .balance;
             if payment_value > *balance {
-                return exit(Return::LackOfFundForGasLimit);
+                return invalid(InvalidTransaction::LackOfFundForGasLimit);
             }
             *balance -= payment_value;
         } else {
-            return exit(Return::OverflowPayment);
+            return invalid(InvalidTransaction::OverflowPaymentInTransaction);
         }
 
         // check if we have enought balance for value transfer.
         let difference = self.data.env.tx.gas_price - self.data.env.effective_gas_price();
         if difference + value > self.data.journaled_state.account(caller).info.balance {
-            return exit(Return::OutOfFund);
+            return invalid(InvalidTransaction::LackOfFundForValue);
         }
 
         // record all as cost;
@@ -131,7 +173,7 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact
             TransactTo::Call(address) => {
                 if self.data.journaled_state.inc_nonce(caller).is_none() {
                     // overflow
-                    return exit(Return::NonceOverflow);
+                    return invalid(InvalidTransaction::NonceOverflowInTransaction);
                 }
                 let context = CallContext {
                     caller,
@@ -180,17 +222,36 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact
             }
         }
 
-        let (state, logs, gas_used, gas_refunded) = self.finalize::<GSPEC>(caller, &gas);
-        (
-            ExecutionResult {
-                exit_reason,
-                out,
+        // Error from database is saved on first occurrence and execution is halted with
+        // FatalExternalError. Return it to the user as it is.
+        if let Some(error) = self.data.error.take() {
+            return Err(EVMError::Database(error));
+        }
+
+        let (state, logs, gas_used, gas_refunded) = self.finalize::<GSPEC>(caller, &gas)?;
+
+        let result = match exit_reason.into() {
+            SuccessOrHalt::Success(reason) => ExecutionResult::Success {
+                reason,
                 gas_used,
                 gas_refunded,
                 logs,
+                output: out,
+            },
+            SuccessOrHalt::Revert => ExecutionResult::Revert {
+                gas_used,
+                output: match out {
+                    TransactOut::Call(return_value) => return_value,
+                    TransactOut::Create(return_value, _) => return_value,
+                    TransactOut::None => Bytes::new(),
+                },
             },
-            state,
-        )
+            SuccessOrHalt::Halt(reason) => ExecutionResult::Halt { reason, gas_used },
+            // Without database error it can only be returned from Inspector or stateful precompile.
+            SuccessOrHalt::FatalExternalError => return Err(EVMError::FatalExternalError),
+        };
+
+        Ok((result, state))
     }
 }
 
@@ -200,11 +261,18 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         env: &'a mut Env,
         inspector: &'a mut dyn Inspector<DB>,
         precompiles: Precompiles,
+        stateful_precompiles: &'a StatefulPrecompiles,
     ) -> Self {
+        let precompile_addresses = precompiles
+            .addresses()
+            .into_iter()
+            .chain(stateful_precompiles.keys())
+            .cloned()
+            .collect();
         let journaled_state = if GSPEC::enabled(SpecId::SPURIOUS_DRAGON) {
-            JournaledState::new(precompiles.len())
+            JournaledState::new(precompile_addresses)
         } else {
-            JournaledState::new_legacy(precompiles.len())
+            JournaledState::new_legacy(precompile_addresses)
         };
         Self {
             data: EVMData {
@@ -214,23 +282,23 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
                 error: None,
             },
             precompiles,
+            stateful_precompiles,
             inspector,
             _phantomdata: PhantomData {},
         }
     }
 
+    #[allow(clippy::type_complexity)]
     fn finalize<SPEC: Spec>(
         &mut self,
         caller: H160,
         gas: &Gas,
-    ) -> (Map<H160, Account>, Vec<Log>, u64, u64) {
+    ) -> Result<(Map<H160, Account>, Vec<Log>, u64, u64), EVMError<DB::Error>> {
         let coinbase = self.data.env.block.coinbase;
         let (gas_used, gas_refunded) = if crate::USE_GAS {
             let effective_gas_price = self.data.env.effective_gas_price();
             let basefee = self.data.env.block.basefee;
-            let max_refund_quotient = if SPEC::enabled(LONDON) { 5 } else { 2 }; // EIP-3529: Reduction in refunds
-
-            let gas_refunded = min(gas.refunded() as u64, gas.spend() / max_refund_quotient);
+            let gas_refunded = gas::transaction_refund(SPEC::SPEC_ID, gas.spend(), gas.refunded());
             let acc_caller = self.data.journaled_state.state().get_mut(&caller).unwrap();
             acc_caller.info.balance = acc_caller
                 .info
@@ -244,11 +312,10 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
                 effective_gas_price
             };
 
-            // TODO
-            let _ = self
-                .data
+            self.data
                 .journaled_state
-                .load_account(coinbase, self.data.db);
+                .load_account(coinbase, self.data.db)
+                .map_err(EVMError::Database)?;
             self.data.journaled_state.touch(&coinbase);
             let acc_coinbase = self
                 .data
@@ -263,11 +330,10 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
             (gas.spend() - gas_refunded, gas_refunded)
         } else {
             // touch coinbase
-            // TODO return
-            let _ = self
-                .data
+            self.data
                 .journaled_state
-                .load_account(coinbase, self.data.db);
+                .load_account(coinbase, self.data.db)
+                .map_err(EVMError::Database)?;
             self.data.journaled_state.touch(&coinbase);
             (0, 0)
         };
@@ -276,28 +342,35 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         // added to it, we need now to load precompile address from db and add this amount to it so that we
         // will have sum.
         if self.data.env.cfg.perf_all_precompiles_have_balance {
-            for address in self.precompiles.addresses() {
+            for address in self.data.journaled_state.precompile_addresses.iter() {
                 if let Some(precompile) = new_state.get_mut(address) {
                     // we found it.
                     precompile.info.balance += self
                         .data
                         .db
                         .basic(*address)
-                        .ok()
-                        .flatten()
+                        .map_err(EVMError::Database)?
                         .map(|acc| acc.balance)
                         .unwrap_or_default();
                 }
             }
         }
 
-        (new_state, logs, gas_used, gas_refunded)
+        Ok((new_state, logs, gas_used, gas_refunded))
     }
 
-    fn initialization<SPEC: Spec>(&mut self) -> u64 {
+    fn initialization<SPEC: Spec>(&mut self) -> Result<u64, EVMError<DB::Error>> {
         let is_create = matches!(self.data.env.tx.transact_to, TransactTo::Create(_));
         let input = &self.data.env.tx.data;
 
+        // EIP-3651: Warm COINBASE. Starts the `COINBASE` address warm
+        if SPEC::enabled(SHANGHAI) {
+            self.data
+                .journaled_state
+                .load_account(self.data.env.block.coinbase, self.data.db)
+                .map_err(EVMError::Database)?;
+        }
+
         if crate::USE_GAS {
             let zero_data_len = input.iter().filter(|v| **v == 0).count() as u64;
             let non_zero_data_len = input.len() as u64 - zero_data_len;
@@ -306,18 +379,16 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
                     let mut accessed_slots = 0_u64;
 
                     for (address, slots) in self.data.env.tx.access_list.iter() {
-                        // TODO return
-                        let _ = self
-                            .data
+                        self.data
                             .journaled_state
-                            .load_account(*address, self.data.db);
+                            .load_account(*address, self.data.db)
+                            .map_err(EVMError::Database)?;
                         accessed_slots += slots.len() as u64;
-                        // TODO return
                         for slot in slots {
-                            let _ = self
-                                .data
+                            self.data
                                 .journaled_state
-                                .sload(*address, *slot, self.data.db);
+                                .sload(*address, *slot, self.data.db)
+                                .map_err(EVMError::Database)?;
                         }
                     }
                     (self.data.env.tx.access_list.len() as u64, accessed_slots)
@@ -340,13 +411,21 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
             // EIP-2028: Transaction data gas cost reduction
             let gas_transaction_non_zero_data = if SPEC::enabled(ISTANBUL) { 16 } else { 68 };
 
-            transact
+            // EIP-3860: Limit and meter initcode
+            let initcode_cost = if SPEC::enabled(SHANGHAI) && is_create {
+                gas::initcode_cost(input.len())
+            } else {
+                0
+            };
+
+            Ok(transact
+                + initcode_cost
                 + zero_data_len * gas::TRANSACTION_ZERO_DATA
                 + non_zero_data_len * gas_transaction_non_zero_data
                 + accessed_accounts * gas::ACCESS_LIST_ADDRESS
-                + accessed_slots * gas::ACCESS_LIST_STORAGE_KEY
+                + accessed_slots * gas::ACCESS_LIST_STORAGE_KEY)
         } else {
-            0
+            Ok(0)
         }
     }
 
@@ -364,6 +443,20 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
             }
         }
 
+        let (ret, address, gas, out) = self.create_contract::<SPEC>(inputs);
+
+        if INSPECT {
+            self.inspector
+                .create_end(&mut self.data, inputs, ret, address, gas, out)
+        } else {
+            (ret, address, gas, out)
+        }
+    }
+
+    fn create_contract<SPEC: Spec>(
+        &mut self,
+        inputs: &CreateInputs,
+    ) -> (Return, Option<H160>, Gas, Bytes) {
         let gas = Gas::new(inputs.gas_limit);
         self.load_account(inputs.caller);
 
@@ -403,7 +496,7 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         // Create contract account and check for collision
         match self.data.journaled_state.create_account(
             created_address,
-            self.precompiles.contains(&created_address),
+            self.is_precompile(&created_address),
             self.data.db,
         ) {
             Ok(false) => {
@@ -418,14 +511,22 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         }
 
         // Transfer value to contract address
-        if let Err(e) = self.data.journaled_state.transfer(
+        match self.data.journaled_state.transfer(
             &inputs.caller,
             &created_address,
             inputs.value,
             self.data.db,
         ) {
-            self.data.journaled_state.checkpoint_revert(checkpoint);
-            return (e, ret, gas, Bytes::new());
+            Ok(None) => (),
+            Ok(Some(e)) => {
+                self.data.journaled_state.checkpoint_revert(checkpoint);
+                return (e, ret, gas, Bytes::new());
+            }
+            Err(err) => {
+                self.data.error = Some(err);
+                self.data.journaled_state.checkpoint_revert(checkpoint);
+                return (Return::FatalExternalError, ret, gas, Bytes::new());
+            }
         }
 
         // EIP-161: State trie clearing (invariant-preserving alternative)
@@ -466,8 +567,8 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         }
         let exit_reason = interp.run::<Self, SPEC>(self);
 
-        // Host error if present on execution\
-        let (ret, address, gas, out) = match exit_reason {
+        // Host error if present on execution
+        match exit_reason {
             return_ok!() => {
                 let b = Bytes::new();
                 // if ok, check contract creation limit and calculate gas deduction on output len.
@@ -482,7 +583,13 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
                 // EIP-170: Contract code size limit
                 // By default limit is 0x6000 (~25kb)
                 if SPEC::enabled(SPURIOUS_DRAGON)
-                    && bytes.len() > self.data.env.cfg.limit_contract_code_size.unwrap_or(0x6000)
+                    && bytes.len()
+                        > self
+                            .data
+                            .env
+                            .cfg
+                            .limit_contract_code_size
+                            .unwrap_or(interpreter::MAX_CODE_SIZE)
                 {
                     self.data.journaled_state.checkpoint_revert(checkpoint);
                     return (Return::CreateContractLimit, ret, interp.gas, b);
@@ -520,13 +627,6 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
                 self.data.journaled_state.checkpoint_revert(checkpoint);
                 (exit_reason, ret, interp.gas, interp.return_value())
             }
-        };
-
-        if INSPECT {
-            self.inspector
-                .create_end(&mut self.data, inputs, ret, address, gas, out)
-        } else {
-            (ret, address, gas, out)
         }
     }
 
@@ -553,7 +653,19 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         let bytecode = if let Some((bytecode, _)) = self.code(inputs.contract) {
             bytecode
         } else {
-            return (Return::FatalExternalError, gas, Bytes::new());
+            let (ret, gas, out) = (Return::FatalExternalError, gas, Bytes::new());
+            if Self::INSPECT {
+                return self.inspector.call_end(
+                    &mut self.data,
+                    inputs,
+                    gas,
+                    ret,
+                    out,
+                    SPEC::IS_STATIC_CALL,
+                );
+            } else {
+                return (ret, gas, out);
+            }
         };
 
         // Check depth
@@ -583,12 +695,19 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         }
 
         // Transfer value from caller to called account
-        if let Err(e) = self.data.journaled_state.transfer(
+        let transfer_ret = match self.data.journaled_state.transfer(
             &inputs.transfer.source,
             &inputs.transfer.target,
             inputs.transfer.value,
             self.data.db,
         ) {
+            Ok(ret) => ret,
+            Err(err) => {
+                self.data.error = Some(err);
+                Some(Return::FatalExternalError)
+            }
+        };
+        if let Some(e) = transfer_ret {
             self.data.journaled_state.checkpoint_revert(checkpoint);
             let (ret, gas, out) = (e, gas, Bytes::new());
             if Self::INSPECT {
@@ -606,12 +725,13 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         }
 
         // Call precompiles
-        let (ret, gas, out) = if let Some(precompile) = self.precompiles.get(&inputs.contract) {
-            let out = match precompile {
-                Precompile::Standard(fun) => fun(inputs.input.as_ref(), inputs.gas_limit),
-                Precompile::Custom(fun) => fun(inputs.input.as_ref(), inputs.gas_limit),
-            };
-            match out {
+        let (ret, gas, out) = if let Some(out) = self.call_precompile::<SPEC>(inputs) {
+            let (ret, gas, out) = match out {
+                // Database error inside of stateful precompile.
+                _ if self.data.error.is_some() => {
+                    self.data.journaled_state.checkpoint_revert(checkpoint);
+                    (Return::FatalExternalError, gas, Bytes::new())
+                }
                 Ok(PrecompileOutput { output, cost, logs }) => {
                     if !crate::USE_GAS || gas.record_cost(cost) {
                         logs.into_iter().for_each(|l| {
@@ -632,7 +752,23 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
                     self.data.journaled_state.checkpoint_revert(checkpoint); //TODO check if we are discarding or reverting
                     (Return::PrecompileError, gas, Bytes::new())
                 }
+            };
+            if INSPECT {
+                // failed precompile consumes all gas given to it.
+                let gas_used = if matches!(ret, return_ok!()) {
+                    gas.spend()
+                } else {
+                    inputs.gas_limit
+                };
+                self.inspector.precompile_call(
+                    &mut self.data,
+                    &inputs.contract,
+                    &inputs.input,
+                    gas_used,
+                    ret,
+                );
             }
+            (ret, gas, out)
         } else {
             // Create interpreter and execute subcall
             let contract =
@@ -670,6 +806,41 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
             (ret, gas, out)
         }
     }
+
+    /// Return true if address is standard or stateful precompile.
+    fn is_precompile(&self, address: &H160) -> bool {
+        self.data
+            .journaled_state
+            .precompile_addresses
+            .contains(address)
+    }
+
+    /// Execute precompile if there is one at called address. Stateful precompiles take precedence.
+    fn call_precompile<SPEC: Spec>(&mut self, inputs: &CallInputs) -> Option<PrecompileResult> {
+        let stateful_precompiles = self.stateful_precompiles;
+        let stateful = stateful_precompiles.get(&inputs.contract);
+        if stateful.is_none() && !self.precompiles.contains(&inputs.contract) {
+            return None;
+        }
+        if INSPECT {
+            self.inspector
+                .precompile_call_start(&mut self.data, &inputs.contract, &inputs.input);
+        }
+        if let Some(precompile) = stateful {
+            return Some(precompile.call(
+                &mut self.data,
+                inputs.input.as_ref(),
+                inputs.gas_limit,
+                &inputs.context,
+                SPEC::IS_STATIC_CALL,
+            ));
+        }
+        let out = match self.precompiles.get(&inputs.contract)? {
+            Precompile::Standard(fun) => fun(inputs.input.as_ref(), inputs.gas_limit),
+            Precompile::Custom(fun) => fun(inputs.input.as_ref(), inputs.gas_limit),
+        };
+        Some(out)
+    }
 }
 
 impl<'a, GSPEC: Spec, DB: Database + 'a, const INSPECT: bool> Host
@@ -732,6 +903,7 @@ impl<'a, GSPEC: Spec, DB: Database + 'a, const INSPECT: bool> Host
 
     /// Get code hash of address.
     fn code_hash(&mut self, address: H160) -> Option<(H256, bool)> {
+        let is_precompile = self.is_precompile(&address);
         let journal = &mut self.data.journaled_state;
         let db = &mut self.data.db;
         let error = &mut self.data.error;
@@ -741,7 +913,6 @@ impl<'a, GSPEC: Spec, DB: Database + 'a, const INSPECT: bool> Host
             .map_err(|e| *error = Some(e))
             .ok()?;
         //asume that all precompiles have some balance
-        let is_precompile = self.precompiles.contains(&address);
         if is_precompile && self.data.env.cfg.perf_all_precompiles_have_balance {
             return Some((KECCAK_EMPTY, is_cold));
         }
@@ -775,6 +946,14 @@ impl<'a, GSPEC: Spec, DB: Database + 'a, const INSPECT: bool> Host
             .ok()
     }
 
+    fn tload(&mut self, address: H160, index: U256) -> U256 {
+        self.data.journaled_state.tload(address, index)
+    }
+
+    fn tstore(&mut self, address: H160, index: U256, value: U256) {
+        self.data.journaled_state.tstore(address, index, value)
+    }
+
     fn log(&mut self, address: H160, topics: Vec<H256>, data: Bytes) {
         if INSPECT {
             self.inspector.log(&mut self.data, &address, &topics, &data);
@@ -789,7 +968,14 @@ impl<'a, GSPEC: Spec, DB: Database + 'a, const INSPECT: bool> Host
 
     fn selfdestruct(&mut self, address: H160, target: H160) -> Option<SelfDestructResult> {
         if INSPECT {
-            self.inspector.selfdestruct();
+            let value = self
+                .data
+                .journaled_state
+                .state
+                .get(&address)
+                .map(|acc| acc.info.balance)
+                .unwrap_or_default();
+            self.inspector.selfdestruct(address, target, value);
         }
         self.data
             .journaled_state
@@ -863,6 +1049,10 @@ pub trait Host {
         index: U256,
         value: U256,
     ) -> Option<(U256, U256, U256, bool)>;
+    /// Get the transient storage value of address at index.
+    fn tload(&mut self, address: H160, index: U256) -> U256;
+    /// Set the transient storage value of address at index.
+    fn tstore(&mut self, address: H160, index: U256, value: U256);
     /// Create a log owned by address with given topics and data.
     fn log(&mut self, address: H160, topics: Vec<H256>, data: Bytes);
     /// Mark an address to be deleted, with funds transferred to target.
@@ -875,3 +1065,272 @@ pub trait Host {
     /// Invoke a call operation.
     fn call<SPEC: Spec>(&mut self, input: &mut CallInputs) -> (Return, Gas, Bytes);
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        opcode, test_utils::bytecode, AccountInfo, CallInputs, Database, EVMData, EVMError,
+        ExecutionResult, Gas, Halt, InMemoryDB, Inspector, InvalidTransaction, Return, SpecId,
+        TransactTo, EVM,
+    };
+    use bytes::Bytes;
+    use primitive_types::{H160, U256};
+
+    fn evm_with_caller_nonce(nonce: u64) -> EVM<InMemoryDB> {
+        let caller = H160::from_low_u64_be(0x1000);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                nonce,
+                ..Default::default()
+            },
+        );
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.caller = caller;
+        evm.env.tx.transact_to = TransactTo::Call(H160::from_low_u64_be(0xaa));
+        evm.env.tx.gas_limit = 100_000;
+        evm.env.cfg.chain_id = U256::one();
+        evm
+    }
+
+    #[test]
+    fn check_nonce() {
+        let mut evm = evm_with_caller_nonce(5);
+        evm.env.tx.nonce = Some(4);
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::NonceTooLow)
+        );
+        evm.env.tx.nonce = Some(6);
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::NonceTooHigh)
+        );
+        evm.env.tx.nonce = Some(5);
+        assert!(evm.transact().is_ok());
+        // transaction without nonce is not checked.
+        evm.env.tx.nonce = None;
+        assert!(evm.transact().is_ok());
+    }
+
+    #[test]
+    fn check_chain_id() {
+        let mut evm = evm_with_caller_nonce(0);
+        evm.env.tx.chain_id = Some(2);
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::InvalidChainId)
+        );
+        evm.env.tx.chain_id = Some(1);
+        assert!(evm.transact().is_ok());
+    }
+
+    #[test]
+    fn disable_nonce_and_chain_id_check() {
+        let mut evm = evm_with_caller_nonce(5);
+        evm.env.tx.nonce = Some(0);
+        evm.env.tx.chain_id = Some(2);
+        evm.env.cfg.disable_nonce_and_chain_id_check = true;
+        assert!(evm.transact().is_ok());
+    }
+
+    fn evm_with_code(code: &[u8]) -> EVM<InMemoryDB> {
+        let mut evm = evm_with_caller_nonce(0);
+        let contract = H160::from_low_u64_be(0xaa);
+        evm.db().unwrap().insert_account_info(
+            contract,
+            AccountInfo {
+                code: Some(bytecode(code)),
+                ..Default::default()
+            },
+        );
+        evm
+    }
+
+    #[test]
+    fn revert_and_halt_results() {
+        let mut evm = evm_with_code(&[opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::REVERT]);
+        match evm.transact().unwrap().0 {
+            ExecutionResult::Revert { gas_used, output } => {
+                assert!(gas_used < 100_000);
+                assert!(output.is_empty());
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let mut evm = evm_with_code(&[opcode::INVALID]);
+        assert_eq!(
+            evm.transact().unwrap().0,
+            ExecutionResult::Halt {
+                reason: Halt::InvalidOpcode,
+                gas_used: 100_000
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_transaction_is_not_executed() {
+        let mut evm = evm_with_caller_nonce(0);
+        evm.env.tx.gas_price = U256::one();
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::LackOfFundForGasLimit)
+        );
+    }
+
+    struct FatalInspector;
+
+    impl<DB: Database> Inspector<DB> for FatalInspector {
+        fn call(
+            &mut self,
+            _data: &mut EVMData<'_, DB>,
+            _inputs: &mut CallInputs,
+            _is_static: bool,
+        ) -> (Return, Gas, Bytes) {
+            (Return::FatalExternalError, Gas::new(0), Bytes::new())
+        }
+    }
+
+    #[test]
+    fn fatal_external_error_of_inspector() {
+        let mut evm = evm_with_caller_nonce(0);
+        assert_eq!(
+            evm.inspect(FatalInspector).unwrap_err(),
+            EVMError::FatalExternalError
+        );
+    }
+
+    fn transact_in(evm: &mut EVM<InMemoryDB>, spec_id: SpecId) -> ExecutionResult {
+        evm.env.cfg.spec_id = spec_id;
+        evm.transact().unwrap().0
+    }
+
+    #[test]
+    fn push0() {
+        let mut evm = evm_with_code(&[opcode::PUSH0, opcode::STOP]);
+        assert_eq!(
+            transact_in(&mut evm, SpecId::MERGE),
+            ExecutionResult::Halt {
+                reason: Halt::NotActivated,
+                gas_used: 100_000
+            }
+        );
+        // intrinsic gas and PUSH0 base gas.
+        assert_eq!(transact_in(&mut evm, SpecId::SHANGHAI).gas_used(), 21_002);
+    }
+
+    #[test]
+    fn warm_coinbase() {
+        let coinbase = H160::from_low_u64_be(0xcb);
+        #[rustfmt::skip]
+        let mut evm = evm_with_code(&[
+            opcode::PUSH1, 0xcb, opcode::BALANCE, opcode::STOP,
+        ]);
+        evm.env.block.coinbase = coinbase;
+        let cold = transact_in(&mut evm, SpecId::MERGE).gas_used();
+        let warm = transact_in(&mut evm, SpecId::SHANGHAI).gas_used();
+        assert_eq!(cold - warm, 2_600 - 100);
+    }
+
+    #[test]
+    fn initcode_limit_and_cost() {
+        let mut evm = evm_with_caller_nonce(0);
+        evm.env.tx.transact_to = TransactTo::create();
+        evm.env.tx.gas_limit = 10_000_000;
+        evm.env.block.gas_limit = U256::from(30_000_000);
+
+        // initcode of zero bytes stops without deploying code.
+        evm.env.tx.data = Bytes::from(vec![0; 33]);
+        let merge = transact_in(&mut evm, SpecId::MERGE).gas_used();
+        let shanghai = transact_in(&mut evm, SpecId::SHANGHAI).gas_used();
+        // two words of initcode.
+        assert_eq!(shanghai - merge, 2 * 2);
+
+        evm.env.tx.data = Bytes::from(vec![0; crate::interpreter::MAX_INITCODE_SIZE + 1]);
+        evm.env.cfg.spec_id = SpecId::SHANGHAI;
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::CreateInitcodeSizeLimit)
+        );
+        assert!(transact_in(&mut evm, SpecId::MERGE).is_success());
+    }
+
+    #[test]
+    fn mcopy() {
+        #[rustfmt::skip]
+        let mut evm = evm_with_code(&[
+            // MSTORE8(1, 0xab), MCOPY(32, 0, 2)
+            opcode::PUSH1, 0xab, opcode::PUSH1, 1, opcode::MSTORE8,
+            opcode::PUSH1, 2, opcode::PUSH1, 0, opcode::PUSH1, 32, opcode::MCOPY,
+            // RETURN(32, 32)
+            opcode::PUSH1, 32, opcode::PUSH1, 32, opcode::RETURN,
+        ]);
+        let result = transact_in(&mut evm, SpecId::CANCUN);
+        let mut expected = [0u8; 32];
+        expected[1] = 0xab;
+        assert_eq!(result.output().unwrap().as_ref(), expected);
+        assert!(!transact_in(&mut evm, SpecId::SHANGHAI).is_success());
+    }
+
+    #[test]
+    fn blob_hash_and_blob_basefee() {
+        #[rustfmt::skip]
+        let mut evm = evm_with_code(&[
+            // MSTORE(0, BLOBHASH(1)), MSTORE(32, BLOBBASEFEE)
+            opcode::PUSH1, 1, opcode::BLOBHASH, opcode::PUSH1, 0, opcode::MSTORE,
+            opcode::BLOBBASEFEE, opcode::PUSH1, 32, opcode::MSTORE,
+            // RETURN(0, 64)
+            opcode::PUSH1, 64, opcode::PUSH1, 0, opcode::RETURN,
+        ]);
+        let hash = primitive_types::H256::repeat_byte(0x01);
+        evm.env.tx.blob_hashes = vec![primitive_types::H256::zero(), hash];
+        evm.env.block.excess_blob_gas = Some(5 * crate::BLOB_GASPRICE_UPDATE_FRACTION);
+        let result = transact_in(&mut evm, SpecId::CANCUN);
+        let output = result.output().unwrap();
+        assert_eq!(&output[..32], hash.as_bytes());
+        assert_eq!(U256::from_big_endian(&output[32..]), U256::from(148));
+    }
+
+    #[cfg(feature = "kzg")]
+    #[test]
+    fn point_evaluation_precompile_in_cancun() {
+        let mut evm = evm_with_caller_nonce(0);
+        evm.env.tx.transact_to = TransactTo::Call(H160::from_low_u64_be(0x0a));
+        // invalid input fails the precompile only after it is activated.
+        evm.env.tx.data = Bytes::from_static(&[0; 192]);
+        assert!(transact_in(&mut evm, SpecId::SHANGHAI).is_success());
+        assert_eq!(
+            transact_in(&mut evm, SpecId::CANCUN),
+            ExecutionResult::Halt {
+                reason: Halt::PrecompileError,
+                gas_used: 100_000
+            }
+        );
+    }
+
+    #[cfg(not(feature = "kzg"))]
+    #[test]
+    fn point_evaluation_precompile_without_kzg_halts() {
+        let mut evm = evm_with_caller_nonce(0);
+        evm.env.tx.transact_to = TransactTo::Call(H160::from_low_u64_be(0x0a));
+        evm.env.tx.data = Bytes::from_static(&[0; 192]);
+        assert_eq!(
+            transact_in(&mut evm, SpecId::CANCUN),
+            ExecutionResult::Halt {
+                reason: Halt::PrecompileError,
+                gas_used: 100_000
+            }
+        );
+        // plain value transfer to it halts the same way.
+        evm.env.tx.data = Bytes::new();
+        assert_eq!(
+            transact_in(&mut evm, SpecId::LATEST),
+            ExecutionResult::Halt {
+                reason: Halt::PrecompileError,
+                gas_used: 100_000
+            }
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use super::constants::*;
-use crate::{models::SelfDestructResult, Spec, SpecId::*};
+use crate::{
+    interpreter::memory::next_multiple_of_32, models::SelfDestructResult, Spec, SpecId, SpecId::*,
+};
+use core::cmp::min;
 use primitive_types::U256;
 
 #[allow(clippy::collapsible_else_if)]
@@ -52,6 +55,17 @@ pub fn sstore_refund<SPEC: Spec>(original: U256, current: U256, new: U256) -> i6
     }
 }
 
+/// Refund returned to the caller at the end of the transaction, capped to a part of spent gas.
+pub fn transaction_refund(spec_id: SpecId, spent: u64, refunded: i64) -> u64 {
+    // EIP-3529: Reduction in refunds
+    let max_refund_quotient = if SpecId::enabled(spec_id, LONDON) {
+        5
+    } else {
+        2
+    };
+    min(refunded.max(0) as u64, spent / max_refund_quotient)
+}
+
 pub fn create2_cost(len: usize) -> Option<u64> {
     let base = CREATE;
     // ceil(len / 32.0)
@@ -63,6 +77,12 @@ pub fn create2_cost(len: usize) -> Option<u64> {
     Some(gas)
 }
 
+/// EIP-3860: Limit and meter initcode. Length is checked against initcode size limit before.
+pub fn initcode_cost(len: usize) -> u64 {
+    let words = next_multiple_of_32(len).unwrap_or(usize::MAX) / 32;
+    INITCODE_WORD_COST * words as u64
+}
+
 fn log2floor(value: U256) -> u64 {
     assert!(!value.is_zero());
     let mut l: u64 = 256;
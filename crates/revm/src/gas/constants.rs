@@ -19,6 +19,7 @@ pub const SHA3WORD: u64 = 6;
 pub const COPY: u64 = 3;
 pub const BLOCKHASH: u64 = 20;
 pub const CODEDEPOSIT: u64 = 200;
+pub const INITCODE_WORD_COST: u64 = 2;
 
 pub const SSTORE_SET: u64 = 20000;
 pub const SSTORE_RESET: u64 = 5000;
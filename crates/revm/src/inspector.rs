@@ -1,5 +1,5 @@
 use bytes::Bytes;
-use primitive_types::{H160, H256};
+use primitive_types::{H160, H256, U256};
 
 use crate::{
     evm_impl::EVMData, opcode, spec_opcode_gas, CallInputs, CreateInputs, Database, Gas,
@@ -7,6 +7,17 @@ use crate::{
 };
 use auto_impl::auto_impl;
 
+mod access_list;
+mod call_tracer;
+#[cfg(feature = "std")]
+mod precompile_profiler;
+mod struct_logger;
+pub use access_list::AccessListInspector;
+pub use call_tracer::{CallFrame, CallKind, CallTracer, CallTracerConfig};
+#[cfg(feature = "std")]
+pub use precompile_profiler::{PrecompileProfiler, PrecompileStats};
+pub use struct_logger::{GethTrace, StructLog, StructLogger, StructLoggerConfig};
+
 #[auto_impl(&mut, Box)]
 pub trait Inspector<DB: Database> {
     /// Called Before the interpreter is initialized.
@@ -118,7 +129,33 @@ pub trait Inspector<DB: Database> {
     }
 
     /// Called when a contract has been self-destructed.
-    fn selfdestruct(&mut self) {}
+    ///
+    /// `value` is the balance of `address` that is going to be transferred to `target`.
+    fn selfdestruct(&mut self, _address: H160, _target: H160, _value: U256) {}
+
+    /// Called right before precompile is executed, after [Inspector::call] and after value is
+    /// transferred.
+    fn precompile_call_start(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _address: &H160,
+        _input: &Bytes,
+    ) {
+    }
+
+    /// Called after precompile is executed, before [Inspector::call_end].
+    ///
+    /// `gas_used` is gas charged by precompile, failed precompile consumes all gas that it got.
+    /// `ret` is [Return::Continue] on success.
+    fn precompile_call(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _address: &H160,
+        _input: &Bytes,
+        _gas_used: u64,
+        _ret: Return,
+    ) {
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -385,7 +422,7 @@ mod tests {
         evm.env.tx.gas_limit = 21100;
 
         let mut inspector = StackInspector::default();
-        let (result, state) = evm.inspect(&mut inspector);
+        let (result, state) = evm.inspect(&mut inspector).unwrap();
         println!("{result:?} {state:?} {inspector:?}");
 
         for (pc, gas) in inspector.gas_remaining_steps {
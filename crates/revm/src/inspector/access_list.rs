@@ -0,0 +1,219 @@
+use crate::{
+    opcode, CallInputs, CreateInputs, Database, EVMData, Gas, Inspector, Interpreter, Return,
+    TransactTo,
+};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use bytes::Bytes;
+use primitive_types::{H160, H256, U256};
+
+/// Inspector that collects EIP-2930 access list of accounts and storage slots used by opcodes.
+///
+/// Access list from transaction is included. Sender, recipient, created contract and
+/// precompiles are excluded as they are always warm, but their accessed storage slots are kept.
+#[derive(Clone, Debug, Default)]
+pub struct AccessListInspector {
+    access_list: BTreeMap<H160, BTreeSet<U256>>,
+}
+
+impl AccessListInspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Access list in the format of `TxEnv::access_list`.
+    pub fn access_list(&self) -> Vec<(H160, Vec<U256>)> {
+        self.access_list
+            .iter()
+            .map(|(address, slots)| (*address, slots.iter().cloned().collect()))
+            .collect()
+    }
+
+    fn is_excluded<DB: Database>(data: &EVMData<'_, DB>, address: &H160) -> bool {
+        *address == data.env.tx.caller
+            || data.env.tx.transact_to == TransactTo::Call(*address)
+            || data.journaled_state.precompile_addresses.contains(address)
+    }
+
+    fn start_transaction<DB: Database>(&mut self, data: &EVMData<'_, DB>) {
+        if data.journaled_state.depth() != 0 {
+            return;
+        }
+        self.access_list.clear();
+        for (address, slots) in data.env.tx.access_list.iter() {
+            if !slots.is_empty() || !Self::is_excluded(data, address) {
+                self.access_list
+                    .entry(*address)
+                    .or_default()
+                    .extend(slots.iter().cloned());
+            }
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for AccessListInspector {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> Return {
+        let address_at = |index| {
+            interp.stack.peek(index).ok().map(|value| {
+                let mut word = H256::zero();
+                value.to_big_endian(word.as_bytes_mut());
+                H160::from(word)
+            })
+        };
+        match interp.current_opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Ok(slot) = interp.stack.peek(0) {
+                    self.access_list
+                        .entry(interp.contract.address)
+                        .or_default()
+                        .insert(slot);
+                }
+            }
+            opcode::BALANCE
+            | opcode::EXTCODESIZE
+            | opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::SELFDESTRUCT => {
+                if let Some(address) = address_at(0) {
+                    if !Self::is_excluded(data, &address) {
+                        self.access_list.entry(address).or_default();
+                    }
+                }
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                if let Some(address) = address_at(1) {
+                    if !Self::is_excluded(data, &address) {
+                        self.access_list.entry(address).or_default();
+                    }
+                }
+            }
+            _ => (),
+        }
+        Return::Continue
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        _inputs: &mut CallInputs,
+        _is_static: bool,
+    ) -> (Return, Gas, Bytes) {
+        self.start_transaction(data);
+        (Return::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        _inputs: &mut CreateInputs,
+    ) -> (Return, Option<H160>, Gas, Bytes) {
+        self.start_transaction(data);
+        (Return::Continue, None, Gas::new(0), Bytes::default())
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        _inputs: &CreateInputs,
+        ret: Return,
+        address: Option<H160>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (Return, Option<H160>, Gas, Bytes) {
+        // contract created by the transaction is warm, only its slots are needed.
+        if let (0, Some(address)) = (data.journaled_state.depth(), address) {
+            if let Some(slots) = self.access_list.get(&address) {
+                if slots.is_empty() {
+                    self.access_list.remove(&address);
+                }
+            }
+        }
+        (ret, address, remaining_gas, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{opcode, test_utils::db_with_code, TransactTo};
+    use primitive_types::{H160, U256};
+
+    #[test]
+    fn create_access_list() {
+        let a = H160::from_low_u64_be(0xaa);
+        let b = H160::from_low_u64_be(0xbb);
+        #[rustfmt::skip]
+        let code_a = [
+            // BALANCE(0xcc)
+            opcode::PUSH1, 0xcc, opcode::BALANCE, opcode::POP,
+            // STATICCALL(gas, 0x04, 0, 0, 0, 0)
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0,
+            opcode::PUSH1, 0x04, opcode::GAS, opcode::STATICCALL, opcode::POP,
+            // CALL(gas, 0xbb, 0, 0, 0, 0, 0)
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0,
+            opcode::PUSH1, 0, opcode::PUSH1, 0xbb, opcode::GAS, opcode::CALL,
+            opcode::STOP,
+        ];
+        // SLOAD(5)
+        let code_b = [opcode::PUSH1, 5, opcode::SLOAD, opcode::STOP];
+        let db = db_with_code(&[(a, &code_a), (b, &code_b)]);
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.transact_to = TransactTo::Call(a);
+        evm.env.tx.gas_limit = 100_000;
+
+        let (result, access_list) = evm.create_access_list().unwrap();
+        assert_eq!(
+            access_list,
+            vec![
+                (b, vec![U256::from(5)]),
+                (H160::from_low_u64_be(0xcc), vec![])
+            ]
+        );
+        assert!(result.is_success());
+
+        let (without_access_list, _) = evm.transact_ref().unwrap();
+        // two accounts and one slot from the access list save 100 gas each.
+        assert_eq!(result.gas_used() + 300, without_access_list.gas_used());
+    }
+
+    #[test]
+    fn keep_storage_of_recipient() {
+        let a = H160::from_low_u64_be(0xaa);
+        let b = H160::from_low_u64_be(0xbb);
+        #[rustfmt::skip]
+        let code = [
+            // SLOAD(1), BALANCE(0xbb)
+            opcode::PUSH1, 1, opcode::SLOAD, opcode::POP,
+            opcode::PUSH1, 0xbb, opcode::BALANCE, opcode::POP,
+            opcode::STOP,
+        ];
+        let db = db_with_code(&[(a, &code)]);
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.transact_to = TransactTo::Call(a);
+        evm.env.tx.gas_limit = 100_000;
+        // recipient without slots is dropped, its slots are kept.
+        evm.env.tx.access_list = vec![(a, vec![U256::from(2)]), (b, vec![])];
+
+        let (result, access_list) = evm.create_access_list().unwrap();
+        assert!(result.is_success());
+        assert_eq!(
+            access_list,
+            vec![(a, vec![U256::one(), U256::from(2)]), (b, vec![])]
+        );
+
+        evm.env.tx.access_list = vec![(a, vec![]), (b, vec![])];
+        let (result, access_list) = evm.create_access_list().unwrap();
+        assert!(result.is_success());
+        assert_eq!(access_list, vec![(a, vec![U256::one()]), (b, vec![])]);
+    }
+}
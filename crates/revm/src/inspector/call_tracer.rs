@@ -0,0 +1,671 @@
+use crate::{
+    gas, interpreter::STACK_LIMIT, opcode, return_ok, return_revert, CallInputs, CallScheme,
+    CreateInputs, CreateScheme, Database, EVMData, Gas, Inspector, Interpreter, Log, Return,
+};
+use alloc::{format, string::String, vec::Vec};
+use bytes::Bytes;
+use primitive_types::{H160, H256, U256};
+#[cfg(feature = "revert_reason")]
+use {crate::RevertDecoder, alloc::string::ToString};
+
+#[cfg(feature = "with-serde")]
+use crate::models::{serde_hex_bytes, serde_hex_bytes_opt, serde_hex_u64};
+
+/// Type of the [CallFrame], serialized same as geth opcode names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "UPPERCASE"))]
+pub enum CallKind {
+    Call,
+    StaticCall,
+    DelegateCall,
+    CallCode,
+    Create,
+    Create2,
+    SelfDestruct,
+}
+
+/// One call of the geth `callTracer` call tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase"))]
+pub struct CallFrame {
+    #[cfg_attr(feature = "with-serde", serde(rename = "type"))]
+    pub kind: CallKind,
+    pub from: H160,
+    /// Called address or created contract address. Empty for failed create.
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub to: Option<H160>,
+    /// Transferred value, empty for `DELEGATECALL` and `STATICCALL`.
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub value: Option<U256>,
+    #[cfg_attr(feature = "with-serde", serde(with = "serde_hex_u64"))]
+    pub gas: u64,
+    #[cfg_attr(feature = "with-serde", serde(with = "serde_hex_u64"))]
+    pub gas_used: u64,
+    #[cfg_attr(feature = "with-serde", serde(with = "serde_hex_bytes"))]
+    pub input: Bytes,
+    /// Returned data, for successful create it is the deployed code.
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "serde_hex_bytes_opt"
+        )
+    )]
+    pub output: Option<Bytes>,
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub error: Option<String>,
+    /// Decoded reason of reverted call, set only with `revert_reason` feature.
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub revert_reason: Option<String>,
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub calls: Vec<CallFrame>,
+    /// Logs emitted directly by this call. Logs of failed calls are removed.
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub logs: Vec<Log>,
+}
+
+impl CallFrame {
+    fn new(kind: CallKind, from: H160, to: Option<H160>, value: Option<U256>, gas: u64) -> Self {
+        Self {
+            kind,
+            from,
+            to,
+            value,
+            gas,
+            gas_used: 0,
+            input: Bytes::new(),
+            output: None,
+            error: None,
+            revert_reason: None,
+            calls: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    fn clear_logs(&mut self) {
+        self.logs.clear();
+        self.calls.iter_mut().for_each(CallFrame::clear_logs);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CallTracerConfig {
+    /// Trace only the transaction call, without nested calls.
+    pub only_top_call: bool,
+    /// Collect logs emitted by calls.
+    pub with_log: bool,
+}
+
+/// Inspector that builds call tree same as geth `callTracer`.
+///
+/// Top frame gas values are for the whole transaction, they include intrinsic gas and refund.
+#[derive(Clone, Debug, Default)]
+pub struct CallTracer {
+    config: CallTracerConfig,
+    stack: Vec<CallFrame>,
+    frame: Option<CallFrame>,
+    /// Opcode and stack length before the last executed instruction, used for error messages.
+    last_step: (u8, usize),
+    #[cfg(feature = "revert_reason")]
+    revert_decoder: RevertDecoder,
+}
+
+impl CallTracer {
+    pub fn new(config: CallTracerConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Set decoder used for revert reasons, it can decode custom errors.
+    #[cfg(feature = "revert_reason")]
+    pub fn revert_decoder(&mut self, decoder: RevertDecoder) {
+        self.revert_decoder = decoder;
+    }
+
+    /// Call tree of the last inspected transaction.
+    pub fn frame(&self) -> Option<&CallFrame> {
+        self.frame.as_ref()
+    }
+
+    pub fn into_frame(self) -> Option<CallFrame> {
+        self.frame
+    }
+
+    fn start_frame<DB: Database>(&mut self, data: &EVMData<'_, DB>, mut frame: CallFrame) {
+        if data.journaled_state.depth() == 0 {
+            frame.gas = data.env.tx.gas_limit;
+        }
+        self.stack.push(frame);
+    }
+
+    fn end_frame<DB: Database>(
+        &mut self,
+        data: &EVMData<'_, DB>,
+        ret: Return,
+        gas: &Gas,
+        out: &Bytes,
+    ) {
+        let mut frame = match self.stack.pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        frame.gas_used = if self.stack.is_empty() {
+            transaction_gas_used(data, ret, gas)
+        } else {
+            match ret {
+                return_ok!() | return_revert!() => frame.gas.saturating_sub(gas.remaining()),
+                _ => frame.gas,
+            }
+        };
+
+        if matches!(ret, return_ok!()) {
+            if !out.is_empty() {
+                frame.output = Some(out.clone());
+            }
+        } else {
+            let (op, stack_len) = self.last_step;
+            frame.error = Some(error_message(ret, op, stack_len));
+            if matches!(frame.kind, CallKind::Create | CallKind::Create2) {
+                frame.to = None;
+            }
+            if ret == Return::Revert && !out.is_empty() {
+                frame.output = Some(out.clone());
+                #[cfg(feature = "revert_reason")]
+                {
+                    frame.revert_reason = self
+                        .revert_decoder
+                        .decode(out)
+                        .map(|reason| reason.to_string());
+                }
+            }
+            frame.clear_logs();
+        }
+
+        match self.stack.last_mut() {
+            Some(parent) => {
+                if !self.config.only_top_call {
+                    parent.calls.push(frame)
+                }
+            }
+            None => self.frame = Some(frame),
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for CallTracer {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> Return {
+        self.last_step = (interp.current_opcode(), interp.stack.len());
+        Return::Continue
+    }
+
+    fn log(&mut self, _data: &mut EVMData<'_, DB>, address: &H160, topics: &[H256], data: &Bytes) {
+        if !self.config.with_log || (self.config.only_top_call && self.stack.len() > 1) {
+            return;
+        }
+        if let Some(frame) = self.stack.last_mut() {
+            frame.logs.push(Log {
+                address: *address,
+                topics: topics.to_vec(),
+                data: data.clone(),
+            });
+        }
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+        _is_static: bool,
+    ) -> (Return, Gas, Bytes) {
+        let (kind, value) = match inputs.context.scheme {
+            CallScheme::Call => (CallKind::Call, Some(inputs.transfer.value)),
+            CallScheme::CallCode => (CallKind::CallCode, Some(inputs.transfer.value)),
+            CallScheme::DelegateCall => (CallKind::DelegateCall, None),
+            CallScheme::StaticCall => (CallKind::StaticCall, None),
+        };
+        let mut frame = CallFrame::new(
+            kind,
+            inputs.transfer.source,
+            Some(inputs.contract),
+            value,
+            inputs.gas_limit,
+        );
+        frame.input = inputs.input.clone();
+        self.start_frame(data, frame);
+
+        (Return::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: Return,
+        out: Bytes,
+        _is_static: bool,
+    ) -> (Return, Gas, Bytes) {
+        self.end_frame(data, ret, &remaining_gas, &out);
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (Return, Option<H160>, Gas, Bytes) {
+        let kind = match inputs.scheme {
+            CreateScheme::Create => CallKind::Create,
+            CreateScheme::Create2 { .. } => CallKind::Create2,
+        };
+        let mut frame = CallFrame::new(
+            kind,
+            inputs.caller,
+            None,
+            Some(inputs.value),
+            inputs.gas_limit,
+        );
+        frame.input = inputs.init_code.clone();
+        self.start_frame(data, frame);
+
+        (Return::Continue, None, Gas::new(0), Bytes::default())
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        _inputs: &CreateInputs,
+        ret: Return,
+        address: Option<H160>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (Return, Option<H160>, Gas, Bytes) {
+        // create returns empty output on success, trace deployed code instead.
+        let code = match (ret, address) {
+            (return_ok!(), Some(address)) => data
+                .journaled_state
+                .state
+                .get(&address)
+                .and_then(|acc| acc.info.code.as_ref())
+                .map(|code| code.bytes().slice(..code.len()))
+                .unwrap_or_default(),
+            _ => out.clone(),
+        };
+        if let Some(frame) = self.stack.last_mut() {
+            frame.to = address;
+        }
+        self.end_frame(data, ret, &remaining_gas, &code);
+        (ret, address, remaining_gas, out)
+    }
+
+    fn selfdestruct(&mut self, address: H160, target: H160, value: U256) {
+        if self.config.only_top_call {
+            return;
+        }
+        if let Some(parent) = self.stack.last_mut() {
+            parent.calls.push(CallFrame::new(
+                CallKind::SelfDestruct,
+                address,
+                Some(target),
+                Some(value),
+                0,
+            ));
+        }
+    }
+}
+
+/// Gas used by the transaction, same as in [crate::ExecutionResult].
+fn transaction_gas_used<DB: Database>(data: &EVMData<'_, DB>, ret: Return, gas: &Gas) -> u64 {
+    let gas_limit = data.env.tx.gas_limit;
+    match ret {
+        return_ok!() => {
+            let spent = gas_limit.saturating_sub(gas.remaining());
+            spent - gas::transaction_refund(data.env.cfg.spec_id, spent, gas.refunded())
+        }
+        return_revert!() => gas_limit.saturating_sub(gas.remaining()),
+        _ => gas_limit,
+    }
+}
+
+/// Error message used by geth for the same failure.
+///
+/// `op` and `stack_len` are opcode and stack length before the failed instruction, geth
+/// includes them in invalid opcode and stack errors.
+pub(super) fn error_message(ret: Return, op: u8, stack_len: usize) -> String {
+    let message = match ret {
+        Return::Revert => "execution reverted",
+        Return::OutOfGas => "out of gas",
+        Return::CallTooDeep => "max call depth exceeded",
+        Return::OutOfFund => "insufficient balance for transfer",
+        Return::CallNotAllowedInsideStatic => "write protection",
+        Return::OpcodeNotFound | Return::InvalidOpcode | Return::NotActivated => {
+            return format!("invalid opcode: {}", op_name(op));
+        }
+        Return::InvalidJump => "invalid jump destination",
+        // geth fails with this error when memory offset or size does not fit into u64.
+        Return::InvalidMemoryRange => "gas uint64 overflow",
+        Return::OutOfOffset => "return data out of bounds",
+        Return::StackUnderflow => {
+            let (inputs, _) = stack_io(op);
+            return format!("stack underflow ({} <=> {})", stack_len, inputs);
+        }
+        Return::StackOverflow => {
+            let (inputs, outputs) = stack_io(op);
+            let limit = STACK_LIMIT as usize + inputs - outputs;
+            return format!("stack limit reached {} ({})", stack_len, limit);
+        }
+        Return::CreateCollision => "contract address collision",
+        Return::CreateContractLimit => "max code size exceeded",
+        Return::CreateContractWithEF => "invalid code: must not begin with 0xef",
+        Return::CreateInitcodeSizeLimit => "max initcode size exceeded",
+        Return::NonceOverflow => "nonce uint64 overflow",
+        // geth does not check balance for overflow, there is no matching error.
+        Return::OverflowPayment => "balance overflow",
+        // geth returns error of the precompile itself, it is not known here.
+        Return::PrecompileError => "precompile failed",
+        _ => "fatal external error",
+    };
+    message.into()
+}
+
+/// Name of the opcode, undefined opcodes are named same as in geth.
+pub(super) fn op_name(op: u8) -> String {
+    match opcode::OPCODE_JUMPMAP[op as usize] {
+        Some(name) => name.into(),
+        None => format!("opcode {:#x} not defined", op),
+    }
+}
+
+/// Number of stack items that opcode takes and pushes, same as in geth jump table.
+fn stack_io(op: u8) -> (usize, usize) {
+    match op {
+        0x01..=0x07 | 0x0a | 0x0b | 0x10..=0x14 | 0x16..=0x18 | 0x1a..=0x1d | opcode::SHA3 => {
+            (2, 1)
+        }
+        opcode::ADDMOD | opcode::MULMOD => (3, 1),
+        opcode::ISZERO
+        | opcode::NOT
+        | opcode::BALANCE
+        | opcode::CALLDATALOAD
+        | opcode::EXTCODESIZE
+        | opcode::EXTCODEHASH
+        | opcode::BLOCKHASH
+        | opcode::BLOBHASH
+        | opcode::MLOAD
+        | opcode::SLOAD
+        | opcode::TLOAD => (1, 1),
+        opcode::ADDRESS
+        | opcode::ORIGIN
+        | opcode::CALLER
+        | opcode::CALLVALUE
+        | opcode::CALLDATASIZE
+        | opcode::CODESIZE
+        | opcode::GASPRICE
+        | opcode::RETURNDATASIZE
+        | 0x41..=0x48
+        | opcode::BLOBBASEFEE
+        | opcode::PC
+        | opcode::MSIZE
+        | opcode::GAS
+        | opcode::PUSH0..=opcode::PUSH32 => (0, 1),
+        opcode::CALLDATACOPY | opcode::CODECOPY | opcode::RETURNDATACOPY | opcode::MCOPY => (3, 0),
+        opcode::EXTCODECOPY => (4, 0),
+        opcode::POP | opcode::JUMP | opcode::SELFDESTRUCT => (1, 0),
+        opcode::MSTORE
+        | opcode::MSTORE8
+        | opcode::SSTORE
+        | opcode::JUMPI
+        | opcode::TSTORE
+        | opcode::RETURN
+        | opcode::REVERT => (2, 0),
+        opcode::DUP1..=opcode::DUP16 => {
+            let n = (op - opcode::DUP1 + 1) as usize;
+            (n, n + 1)
+        }
+        opcode::SWAP1..=opcode::SWAP16 => {
+            let n = (op - opcode::SWAP1 + 2) as usize;
+            (n, n)
+        }
+        opcode::LOG0..=opcode::LOG4 => ((op - opcode::LOG0 + 2) as usize, 0),
+        opcode::CREATE => (3, 1),
+        opcode::CREATE2 => (4, 1),
+        opcode::CALL | opcode::CALLCODE => (7, 1),
+        opcode::DELEGATECALL | opcode::STATICCALL => (6, 1),
+        _ => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::db_with_code, ExecutionResult, InMemoryDB, SpecId, TransactTo};
+
+    fn trace_code(code: &[u8], spec_id: SpecId) -> (ExecutionResult, CallFrame) {
+        let contract = H160::from_low_u64_be(0xaa);
+        let db = db_with_code(&[(contract, code)]);
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.cfg.spec_id = spec_id;
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        evm.env.tx.gas_limit = 100_000;
+
+        let mut tracer = CallTracer::new(CallTracerConfig::default());
+        let (result, _) = evm.inspect(&mut tracer).unwrap();
+        (result, tracer.into_frame().unwrap())
+    }
+
+    #[test]
+    fn trace_reverted_subcall() {
+        let caller = H160::from_low_u64_be(0x1000);
+        let a = H160::from_low_u64_be(0xaa);
+        let b = H160::from_low_u64_be(0xbb);
+        #[rustfmt::skip]
+        let code_a = [
+            // CALL(gas, 0xbb, 0, 0, 0, 0, 0)
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0,
+            opcode::PUSH1, 0, opcode::PUSH1, 0xbb, opcode::GAS, opcode::CALL,
+            // LOG0(0, 0)
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::LOG0,
+            opcode::STOP,
+        ];
+        #[rustfmt::skip]
+        let code_b = [
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::LOG0,
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::REVERT,
+        ];
+        let db = db_with_code(&[(a, &code_a), (b, &code_b)]);
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.caller = caller;
+        evm.env.tx.transact_to = TransactTo::Call(a);
+        evm.env.tx.gas_limit = 100_000;
+
+        let mut tracer = CallTracer::new(CallTracerConfig {
+            with_log: true,
+            ..Default::default()
+        });
+        let (result, _) = evm.inspect(&mut tracer).unwrap();
+        let frame = tracer.into_frame().unwrap();
+
+        assert_eq!(frame.kind, CallKind::Call);
+        assert_eq!((frame.from, frame.to), (caller, Some(a)));
+        assert_eq!(frame.gas, 100_000);
+        assert_eq!(frame.gas_used, result.gas_used());
+        assert_eq!(frame.error, None);
+        assert_eq!(frame.logs.len(), 1);
+
+        assert_eq!(frame.calls.len(), 1);
+        let call = &frame.calls[0];
+        assert_eq!((call.from, call.to), (a, Some(b)));
+        assert_eq!(call.error.as_deref(), Some("execution reverted"));
+        assert!(call.logs.is_empty());
+    }
+
+    #[test]
+    fn top_frame_gas_used_includes_refund() {
+        #[rustfmt::skip]
+        let code = [
+            // SSTORE(0, 1), SSTORE(0, 0)
+            opcode::PUSH1, 1, opcode::PUSH1, 0, opcode::SSTORE,
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::SSTORE,
+            opcode::STOP,
+        ];
+        for spec_id in [SpecId::ISTANBUL, SpecId::LONDON] {
+            let (result, frame) = trace_code(&code, spec_id);
+            match result {
+                ExecutionResult::Success { gas_refunded, .. } => assert!(gas_refunded > 0),
+                _ => panic!("unexpected result {:?}", result),
+            }
+            assert_eq!(frame.gas_used, result.gas_used());
+        }
+    }
+
+    #[test]
+    fn top_frame_gas_used_of_failed_transaction() {
+        #[rustfmt::skip]
+        let revert = [
+            opcode::PUSH1, 1, opcode::PUSH1, 0, opcode::SSTORE,
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::REVERT,
+        ];
+        let (result, frame) = trace_code(&revert, SpecId::LATEST);
+        assert!(matches!(result, ExecutionResult::Revert { .. }));
+        assert_eq!(frame.gas_used, result.gas_used());
+        assert!(frame.gas_used < frame.gas);
+
+        let (result, frame) = trace_code(&[opcode::INVALID], SpecId::LATEST);
+        assert!(matches!(result, ExecutionResult::Halt { .. }));
+        assert_eq!(frame.gas_used, result.gas_used());
+        assert_eq!(frame.gas_used, frame.gas);
+    }
+
+    #[test]
+    fn trace_geth_error_messages() {
+        // RETURNDATACOPY(0, 0, 1) without return data.
+        #[rustfmt::skip]
+        let returndatacopy = [
+            opcode::PUSH1, 1, opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::RETURNDATACOPY,
+        ];
+        // push ADDRESS in a loop until the stack is full.
+        #[rustfmt::skip]
+        let stack_overflow = [
+            opcode::JUMPDEST, opcode::ADDRESS, opcode::PUSH1, 0, opcode::JUMP,
+        ];
+        let cases: [(&[u8], SpecId, &str); 7] = [
+            (
+                &[opcode::PUSH1, 1, opcode::ADD],
+                SpecId::LATEST,
+                "stack underflow (1 <=> 2)",
+            ),
+            (
+                &[opcode::SWAP1],
+                SpecId::LATEST,
+                "stack underflow (0 <=> 2)",
+            ),
+            (
+                &stack_overflow,
+                SpecId::LATEST,
+                "stack limit reached 1024 (1023)",
+            ),
+            (
+                &[opcode::INVALID],
+                SpecId::LATEST,
+                "invalid opcode: INVALID",
+            ),
+            (
+                &[0x0c],
+                SpecId::LATEST,
+                "invalid opcode: opcode 0xc not defined",
+            ),
+            (&[opcode::PUSH0], SpecId::MERGE, "invalid opcode: PUSH0"),
+            (&returndatacopy, SpecId::LATEST, "return data out of bounds"),
+        ];
+        for (code, spec_id, error) in cases {
+            let (_, frame) = trace_code(code, spec_id);
+            assert_eq!(frame.error.as_deref(), Some(error));
+        }
+    }
+
+    #[test]
+    fn error_message_stack_limits() {
+        assert_eq!(
+            error_message(Return::StackOverflow, opcode::DUP16, 1024),
+            "stack limit reached 1024 (1023)"
+        );
+        assert_eq!(
+            error_message(Return::StackUnderflow, opcode::SWAP16, 16),
+            "stack underflow (16 <=> 17)"
+        );
+        assert_eq!(
+            error_message(Return::StackUnderflow, opcode::CALL, 6),
+            "stack underflow (6 <=> 7)"
+        );
+        assert_eq!(
+            error_message(Return::StackUnderflow, opcode::LOG4, 5),
+            "stack underflow (5 <=> 6)"
+        );
+        assert_eq!(
+            error_message(Return::OutOfOffset, opcode::RETURNDATACOPY, 3),
+            "return data out of bounds"
+        );
+    }
+
+    #[test]
+    fn trace_failed_create() {
+        let caller = H160::from_low_u64_be(0x11);
+        // init code returns code that starts with 0xef.
+        #[rustfmt::skip]
+        let init_code = [
+            opcode::PUSH1, 0xef, opcode::PUSH1, 0, opcode::MSTORE8,
+            opcode::PUSH1, 1, opcode::PUSH1, 0, opcode::RETURN,
+        ];
+        let mut evm = crate::new();
+        evm.database(InMemoryDB::default());
+        evm.env.tx.caller = caller;
+        evm.env.tx.transact_to = TransactTo::create();
+        evm.env.tx.data = Bytes::copy_from_slice(&init_code);
+        evm.env.tx.gas_limit = 100_000;
+
+        let mut tracer = CallTracer::new(CallTracerConfig::default());
+        let (result, _) = evm.inspect(&mut tracer).unwrap();
+        let frame = tracer.into_frame().unwrap();
+
+        assert_eq!(frame.kind, CallKind::Create);
+        assert_eq!(frame.to, None);
+        assert_eq!(
+            frame.error.as_deref(),
+            Some("invalid code: must not begin with 0xef")
+        );
+        assert_eq!(frame.gas_used, result.gas_used());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{Database, EVMData, Inspector, Return};
+use bytes::Bytes;
+use hashbrown::HashMap as Map;
+use primitive_types::H160;
+use std::time::{Duration, Instant};
+
+/// Aggregated statistics of one precompile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PrecompileStats {
+    /// Number of times precompile was called.
+    pub calls: u64,
+    /// Number of calls that failed or ran out of gas.
+    pub failed: u64,
+    /// Total gas used by all calls.
+    pub gas_used: u64,
+    /// Total wall time spent in precompile execution.
+    pub time: Duration,
+}
+
+/// Inspector that collects call count, gas and time spent in every precompile.
+///
+/// Stats are kept between transactions, use [PrecompileProfiler::reset] to clear them.
+#[derive(Clone, Debug, Default)]
+pub struct PrecompileProfiler {
+    stats: Map<H160, PrecompileStats>,
+    call_start: Option<Instant>,
+}
+
+impl PrecompileProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stats of all called precompiles.
+    pub fn stats(&self) -> &Map<H160, PrecompileStats> {
+        &self.stats
+    }
+
+    pub fn reset(&mut self) {
+        self.stats.clear();
+    }
+}
+
+impl<DB: Database> Inspector<DB> for PrecompileProfiler {
+    fn precompile_call_start(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _address: &H160,
+        _input: &Bytes,
+    ) {
+        self.call_start = Some(Instant::now());
+    }
+
+    fn precompile_call(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        address: &H160,
+        _input: &Bytes,
+        gas_used: u64,
+        ret: Return,
+    ) {
+        let stats = self.stats.entry(*address).or_default();
+        stats.calls += 1;
+        stats.gas_used += gas_used;
+        if ret != Return::Continue {
+            stats.failed += 1;
+        }
+        if let Some(start) = self.call_start.take() {
+            stats.time += start.elapsed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InMemoryDB, TransactTo};
+
+    #[test]
+    fn profile_identity_precompile() {
+        let identity = H160::from_low_u64_be(4);
+        let mut evm = crate::new();
+        evm.database(InMemoryDB::default());
+        evm.env.tx.transact_to = TransactTo::Call(identity);
+        evm.env.tx.data = Bytes::from_static(&[1; 40]);
+
+        let mut profiler = PrecompileProfiler::new();
+        evm.inspect(&mut profiler).unwrap();
+        evm.inspect(&mut profiler).unwrap();
+
+        let stats = profiler.stats()[&identity];
+        assert_eq!(stats.calls, 2);
+        assert_eq!(stats.failed, 0);
+        // 15 base and 3 per word.
+        assert_eq!(stats.gas_used, 2 * (15 + 3 * 2));
+    }
+
+    /// Inspector whose call hook is slow, its time should not be counted as precompile time.
+    struct SlowCall(PrecompileProfiler);
+
+    impl<DB: Database> Inspector<DB> for SlowCall {
+        fn call(
+            &mut self,
+            _data: &mut EVMData<'_, DB>,
+            _inputs: &mut crate::CallInputs,
+            _is_static: bool,
+        ) -> (Return, crate::Gas, Bytes) {
+            std::thread::sleep(Duration::from_millis(50));
+            (Return::Continue, crate::Gas::new(0), Bytes::new())
+        }
+
+        fn precompile_call_start(
+            &mut self,
+            data: &mut EVMData<'_, DB>,
+            address: &H160,
+            input: &Bytes,
+        ) {
+            self.0.precompile_call_start(data, address, input)
+        }
+
+        fn precompile_call(
+            &mut self,
+            data: &mut EVMData<'_, DB>,
+            address: &H160,
+            input: &Bytes,
+            gas_used: u64,
+            ret: Return,
+        ) {
+            self.0.precompile_call(data, address, input, gas_used, ret)
+        }
+    }
+
+    #[test]
+    fn time_only_precompile_execution() {
+        let identity = H160::from_low_u64_be(4);
+        let mut evm = crate::new();
+        evm.database(InMemoryDB::default());
+        evm.env.tx.transact_to = TransactTo::Call(identity);
+
+        let mut inspector = SlowCall(PrecompileProfiler::new());
+        evm.inspect(&mut inspector).unwrap();
+
+        let stats = inspector.0.stats()[&identity];
+        assert_eq!(stats.calls, 1);
+        assert!(stats.time < Duration::from_millis(50));
+    }
+}
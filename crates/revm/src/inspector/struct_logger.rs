@@ -0,0 +1,600 @@
+use super::call_tracer::{error_message, op_name};
+use crate::{
+    opcode, return_ok, CallInputs, CreateInputs, Database, EVMData, ExecutionResult, Gas,
+    GasInspector, Inspector, Interpreter, Return,
+};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use bytes::Bytes;
+use core::fmt::Write;
+use hashbrown::HashMap as Map;
+use primitive_types::{H160, U256};
+
+#[cfg(feature = "with-serde")]
+use crate::models::serde_hex_bytes;
+
+/// One executed instruction.
+///
+/// With `with-serde` it serializes as geth `structLogs` entry, use [StructLog::eip3155_json] for
+/// EIP-3155 line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase"))]
+pub struct StructLog {
+    pub pc: u64,
+    #[cfg_attr(feature = "with-serde", serde(serialize_with = "serde_op::serialize"))]
+    pub op: u8,
+    /// Gas remaining before the instruction.
+    pub gas: u64,
+    pub gas_cost: u64,
+    /// Call depth, starts from 1.
+    pub depth: u64,
+    #[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub stack: Option<Vec<U256>>,
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "serde_memory::serialize"
+        )
+    )]
+    pub memory: Option<Bytes>,
+    #[cfg_attr(feature = "with-serde", serde(skip))]
+    pub mem_size: u64,
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "serde_return_data::serialize"
+        )
+    )]
+    pub return_data: Option<Bytes>,
+    /// Storage of the current contract seen so far, it is set only for `SLOAD` and `SSTORE`.
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "serde_storage::serialize"
+        )
+    )]
+    pub storage: Option<BTreeMap<U256, U256>>,
+    /// Refund counter of the transaction, it includes refunds of the parent calls and finished
+    /// subcalls that were not reverted.
+    #[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "is_zero"))]
+    pub refund: u64,
+    #[cfg_attr(feature = "with-serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub error: Option<String>,
+}
+
+impl StructLog {
+    /// Name of the opcode, unknown opcodes are named same as in geth.
+    pub fn op_name(&self) -> String {
+        op_name(self.op)
+    }
+
+    /// Serialize as one line of EIP-3155 trace.
+    pub fn eip3155_json(&self) -> String {
+        let mut out = format!(
+            "{{\"pc\":{},\"op\":{},\"gas\":\"{:#x}\",\"gasCost\":\"{:#x}\"",
+            self.pc, self.op, self.gas, self.gas_cost
+        );
+        if let Some(memory) = &self.memory {
+            let _ = write!(out, ",\"memory\":\"0x{}\"", hex_encode(memory));
+        }
+        let _ = write!(out, ",\"memSize\":{}", self.mem_size);
+        out.push_str(",\"stack\":[");
+        for (i, value) in self.stack.iter().flatten().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            let _ = write!(out, "{}\"{:#x}\"", sep, value);
+        }
+        out.push(']');
+        if let Some(return_data) = &self.return_data {
+            let _ = write!(out, ",\"returnData\":\"0x{}\"", hex_encode(return_data));
+        }
+        let _ = write!(
+            out,
+            ",\"depth\":{},\"refund\":{},\"opName\":\"{}\"",
+            self.depth,
+            self.refund,
+            self.op_name()
+        );
+        if let Some(error) = &self.error {
+            let _ = write!(out, ",\"error\":\"{}\"", error);
+        }
+        out.push('}');
+        out
+    }
+}
+
+/// Result of the transaction in geth `structLogs` format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "with-serde", serde(rename_all = "camelCase"))]
+pub struct GethTrace {
+    pub gas: u64,
+    pub failed: bool,
+    #[cfg_attr(feature = "with-serde", serde(with = "serde_hex_bytes"))]
+    pub return_value: Bytes,
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// What is captured on every step. Field names follow geth logger config.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StructLoggerConfig {
+    pub disable_stack: bool,
+    pub enable_memory: bool,
+    pub disable_storage: bool,
+    pub enable_return_data: bool,
+}
+
+/// Inspector that records every executed instruction.
+///
+/// Logs are cleared at the start of every transaction. They can be turned into EIP-3155 lines
+/// with [StructLogger::eip3155_lines] or into geth trace with [StructLogger::geth_trace].
+#[derive(Clone, Debug, Default)]
+pub struct StructLogger {
+    config: StructLoggerConfig,
+    gas_inspector: GasInspector,
+    logs: Vec<StructLog>,
+    /// Index of log, remaining gas and stack length for instructions that are not yet finished.
+    pending: Vec<(usize, u64, usize)>,
+    /// Index of the storage slot loaded by current `SLOAD`.
+    sload_index: Option<U256>,
+    storage: Map<H160, BTreeMap<U256, U256>>,
+    /// Refund of every active call seen on its last step, starting from the top call.
+    refunds: Vec<i64>,
+}
+
+impl StructLogger {
+    pub fn new(config: StructLoggerConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn logs(&self) -> &[StructLog] {
+        &self.logs
+    }
+
+    pub fn into_logs(self) -> Vec<StructLog> {
+        self.logs
+    }
+
+    /// EIP-3155 trace lines, last line is the summary of the transaction.
+    pub fn eip3155_lines(&self, result: &ExecutionResult) -> impl Iterator<Item = String> + '_ {
+        let output = result
+            .output()
+            .map(|out| hex_encode(out))
+            .unwrap_or_default();
+        let summary = format!(
+            "{{\"output\":\"{}\",\"gasUsed\":\"{:#x}\",\"pass\":{}}}",
+            output,
+            result.gas_used(),
+            result.is_success()
+        );
+        self.logs
+            .iter()
+            .map(StructLog::eip3155_json)
+            .chain(core::iter::once(summary))
+    }
+
+    pub fn geth_trace(&self, result: &ExecutionResult) -> GethTrace {
+        GethTrace {
+            gas: result.gas_used(),
+            failed: !result.is_success(),
+            return_value: result.output().cloned().unwrap_or_default(),
+            struct_logs: self.logs.clone(),
+        }
+    }
+
+    fn start_transaction<DB: Database>(&mut self, data: &EVMData<'_, DB>) {
+        if data.journaled_state.depth() == 0 {
+            self.logs.clear();
+            self.pending.clear();
+            self.storage.clear();
+            self.refunds.clear();
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StructLogger {
+    fn initialize_interp(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> Return {
+        self.gas_inspector
+            .initialize_interp(interp, data, is_static);
+        Return::Continue
+    }
+
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> Return {
+        let op = interp.current_opcode();
+        let gas = self.gas_inspector.gas_remaining();
+        self.gas_inspector.step(interp, data, is_static);
+
+        let storage = if !self.config.disable_storage && op == opcode::SSTORE {
+            let storage = self.storage.entry(interp.contract.address).or_default();
+            if let (Ok(index), Ok(value)) = (interp.stack.peek(0), interp.stack.peek(1)) {
+                storage.insert(index, value);
+            }
+            Some(storage.clone())
+        } else {
+            if op == opcode::SLOAD {
+                self.sload_index = interp.stack.peek(0).ok();
+            }
+            None
+        };
+
+        self.pending
+            .push((self.logs.len(), gas, interp.stack.len()));
+        // refund of the call includes refunds of its finished subcalls.
+        let depth = data.journaled_state.depth();
+        self.refunds.resize(depth as usize, 0);
+        if let Some(refund) = self.refunds.last_mut() {
+            *refund = interp.gas.refunded();
+        }
+        let refund = self.refunds.iter().sum::<i64>().max(0) as u64;
+        self.logs.push(StructLog {
+            pc: interp.program_counter() as u64,
+            op,
+            gas,
+            gas_cost: 0,
+            depth,
+            stack: (!self.config.disable_stack).then(|| interp.stack.data().clone()),
+            memory: self
+                .config
+                .enable_memory
+                .then(|| Bytes::copy_from_slice(interp.memory.data())),
+            mem_size: interp.memory.len() as u64,
+            return_data: self
+                .config
+                .enable_return_data
+                .then(|| interp.return_data_buffer.clone()),
+            storage,
+            refund,
+            error: None,
+        });
+
+        Return::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+        eval: Return,
+    ) -> Return {
+        self.gas_inspector.step_end(interp, data, is_static, eval);
+
+        if let Some((index, gas, stack_len)) = self.pending.pop() {
+            let log = &mut self.logs[index];
+            log.gas_cost = gas.saturating_sub(self.gas_inspector.gas_remaining());
+            if !matches!(eval, return_ok!() | Return::Revert) {
+                log.error = Some(error_message(eval, log.op, stack_len));
+            }
+            // loaded value is known only after the execution.
+            if !self.config.disable_storage && log.op == opcode::SLOAD {
+                let storage = self.storage.entry(interp.contract.address).or_default();
+                if let (Some(index), Ok(value)) = (self.sload_index.take(), interp.stack.peek(0)) {
+                    storage.insert(index, value);
+                }
+                log.storage = Some(storage.clone());
+            }
+        }
+
+        Return::Continue
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        _inputs: &mut CallInputs,
+        _is_static: bool,
+    ) -> (Return, Gas, Bytes) {
+        self.start_transaction(data);
+        (Return::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: Return,
+        out: Bytes,
+        is_static: bool,
+    ) -> (Return, Gas, Bytes) {
+        self.gas_inspector
+            .call_end(data, inputs, remaining_gas, ret, out.clone(), is_static);
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        _inputs: &mut CreateInputs,
+    ) -> (Return, Option<H160>, Gas, Bytes) {
+        self.start_transaction(data);
+        (Return::Continue, None, Gas::new(0), Bytes::default())
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CreateInputs,
+        ret: Return,
+        address: Option<H160>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (Return, Option<H160>, Gas, Bytes) {
+        self.gas_inspector
+            .create_end(data, inputs, ret, address, remaining_gas, out.clone());
+        (ret, address, remaining_gas, out)
+    }
+}
+
+fn hex_encode(data: &[u8]) -> String {
+    data.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{:02x}", byte);
+        out
+    })
+}
+
+#[cfg(feature = "with-serde")]
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Serde functions to serde opcode as its name.
+#[cfg(feature = "with-serde")]
+mod serde_op {
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(op: &u8, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&super::op_name(*op))
+    }
+}
+
+/// Serde functions to serde memory as 32 byte words, same as geth.
+#[cfg(feature = "with-serde")]
+mod serde_memory {
+    use bytes::Bytes;
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(memory: &Option<Bytes>, s: S) -> Result<S::Ok, S::Error> {
+        let memory = memory.as_deref().unwrap_or_default();
+        s.collect_seq(memory.chunks(32).map(super::hex_encode))
+    }
+}
+
+/// Serde functions to serde an Option [bytes::Bytes] as hex string.
+#[cfg(feature = "with-serde")]
+mod serde_return_data {
+    use bytes::Bytes;
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(data: &Option<Bytes>, s: S) -> Result<S::Ok, S::Error> {
+        crate::models::serde_hex_bytes::serialize(data.as_deref().unwrap_or_default(), s)
+    }
+}
+
+/// Serde functions to serde storage as map of 32 byte hex words, same as geth.
+#[cfg(feature = "with-serde")]
+mod serde_storage {
+    use alloc::{collections::BTreeMap, format};
+    use primitive_types::U256;
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(
+        storage: &Option<BTreeMap<U256, U256>>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        s.collect_map(
+            storage
+                .iter()
+                .flatten()
+                .map(|(index, value)| (format!("{:064x}", index), format!("{:064x}", value))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::db_with_code, TransactTo};
+
+    #[test]
+    fn log_sstore_and_sload() {
+        let contract = H160::from_low_u64_be(0xaa);
+        #[rustfmt::skip]
+        let code = [
+            // SSTORE(1, 2)
+            opcode::PUSH1, 2, opcode::PUSH1, 1, opcode::SSTORE,
+            // SLOAD(1)
+            opcode::PUSH1, 1, opcode::SLOAD,
+            opcode::STOP,
+        ];
+        let db = db_with_code(&[(contract, &code)]);
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        evm.env.tx.gas_limit = 100_000;
+
+        let mut logger = StructLogger::new(StructLoggerConfig::default());
+        let (result, _) = evm.inspect(&mut logger).unwrap();
+        assert!(result.is_success());
+
+        let ops: Vec<_> = logger.logs().iter().map(|log| log.op).collect();
+        assert_eq!(
+            ops,
+            [
+                opcode::PUSH1,
+                opcode::PUSH1,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                opcode::SLOAD,
+                opcode::STOP
+            ]
+        );
+        let logs = logger.logs();
+        assert!(logs.iter().all(|log| log.depth == 1));
+        assert_eq!(logs[0].gas, 100_000 - 21_000);
+        assert_eq!(logs[0].gas_cost, 3);
+        assert_eq!(logs[1].gas, logs[0].gas - 3);
+        // cold sstore from zero to non zero.
+        assert_eq!(logs[2].gas_cost, 22_100);
+        // warm sload.
+        assert_eq!(logs[4].gas_cost, 100);
+
+        let storage = BTreeMap::from([(U256::one(), U256::from(2))]);
+        assert_eq!(logs[2].storage.as_ref(), Some(&storage));
+        assert_eq!(logs[4].storage.as_ref(), Some(&storage));
+        assert_eq!(logs[3].storage, None);
+
+        assert_eq!(
+            logs[2].eip3155_json(),
+            format!(
+                "{{\"pc\":4,\"op\":85,\"gas\":\"{:#x}\",\"gasCost\":\"0x5654\",\"memSize\":0,\
+                \"stack\":[\"0x2\",\"0x1\"],\"depth\":1,\"refund\":0,\"opName\":\"SSTORE\"}}",
+                logs[2].gas
+            )
+        );
+        let summary = logger.eip3155_lines(&result).last().unwrap();
+        assert_eq!(
+            summary,
+            format!(
+                "{{\"output\":\"\",\"gasUsed\":\"{:#x}\",\"pass\":true}}",
+                result.gas_used()
+            )
+        );
+    }
+
+    #[test]
+    fn refund_is_cumulative_across_calls() {
+        let a = H160::from_low_u64_be(0xaa);
+        let b = H160::from_low_u64_be(0xbb);
+        #[rustfmt::skip]
+        let code_a = [
+            // SSTORE(0, 0), clears slot
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::SSTORE,
+            // CALL(gas, 0xbb, 0, 0, 0, 0, 0)
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::PUSH1, 0,
+            opcode::PUSH1, 0, opcode::PUSH1, 0xbb, opcode::GAS, opcode::CALL,
+            opcode::STOP,
+        ];
+        #[rustfmt::skip]
+        let code_b = [
+            opcode::PUSH1, 0, opcode::PUSH1, 0, opcode::SSTORE,
+            opcode::STOP,
+        ];
+        let mut db = db_with_code(&[(a, &code_a), (b, &code_b)]);
+        for address in [a, b] {
+            db.insert_account_storage(address, U256::zero(), U256::one())
+                .unwrap();
+        }
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.transact_to = TransactTo::Call(a);
+        evm.env.tx.gas_limit = 100_000;
+
+        let mut logger = StructLogger::new(StructLoggerConfig::default());
+        let (result, _) = evm.inspect(&mut logger).unwrap();
+        assert!(result.is_success());
+
+        // EIP-3529 refund for clearing a slot.
+        let clear_refund = 4800;
+        let refunds: Vec<_> = logger
+            .logs()
+            .iter()
+            .map(|log| (log.depth, log.op, log.refund))
+            .collect();
+        // subcall sees refund of the parent, parent sees refund of the finished subcall.
+        assert_eq!(
+            &refunds[refunds.len() - 6..],
+            [
+                (1, opcode::CALL, clear_refund),
+                (2, opcode::PUSH1, clear_refund),
+                (2, opcode::PUSH1, clear_refund),
+                (2, opcode::SSTORE, clear_refund),
+                (2, opcode::STOP, 2 * clear_refund),
+                (1, opcode::STOP, 2 * clear_refund),
+            ]
+            .as_slice()
+        );
+    }
+
+    #[cfg(feature = "with-serde")]
+    #[test]
+    fn serialize_geth_trace() {
+        let log = StructLog {
+            pc: 7,
+            op: opcode::SLOAD,
+            gas: 100,
+            gas_cost: 2100,
+            depth: 1,
+            stack: Some(vec![U256::from(1)]),
+            memory: Some(Bytes::from([[0xab; 32], [0; 32]].concat())),
+            mem_size: 64,
+            return_data: None,
+            storage: Some(BTreeMap::from([(U256::one(), U256::from(2))])),
+            refund: 4800,
+            error: None,
+        };
+        let trace = GethTrace {
+            gas: 21_000,
+            failed: false,
+            return_value: Bytes::from_static(&[0x01]),
+            struct_logs: vec![
+                log.clone(),
+                StructLog {
+                    op: 0x0c,
+                    stack: None,
+                    memory: None,
+                    storage: None,
+                    refund: 0,
+                    error: Some("invalid opcode: opcode 0xc not defined".into()),
+                    ..log
+                },
+            ],
+        };
+        let expected = serde_json::json!({
+            "gas": 21000,
+            "failed": false,
+            "returnValue": "0x01",
+            "structLogs": [
+                {
+                    "pc": 7,
+                    "op": "SLOAD",
+                    "gas": 100,
+                    "gasCost": 2100,
+                    "depth": 1,
+                    "stack": ["0x1"],
+                    "memory": ["ab".repeat(32), "00".repeat(32)],
+                    "storage": {
+                        format!("{:064x}", 1): format!("{:064x}", 2),
+                    },
+                    "refund": 4800,
+                },
+                {
+                    "pc": 7,
+                    "op": "opcode 0xc not defined",
+                    "gas": 100,
+                    "gasCost": 2100,
+                    "depth": 1,
+                    "error": "invalid opcode: opcode 0xc not defined",
+                },
+            ],
+        });
+        assert_eq!(serde_json::to_value(&trace).unwrap(), expected);
+    }
+}
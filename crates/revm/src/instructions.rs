@@ -13,7 +13,7 @@ mod system;
 
 pub use opcode::{OpCode, OPCODE_JUMPMAP};
 
-use crate::{interpreter::Interpreter, CallScheme, Host, Spec, SpecId::*};
+use crate::{interpreter::Interpreter, CallScheme, Eval, Halt, Host, Spec, SpecId::*};
 use core::ops::{BitAnd, BitOr, BitXor};
 use primitive_types::U256;
 
@@ -58,12 +58,6 @@ pub enum Return {
     StackOverflow,
     OutOfOffset,
     FatalExternalError,
-    GasMaxFeeGreaterThanPriorityFee,
-    GasPriceLessThenBasefee,
-    CallerGasLimitMoreThenBlock,
-    /// EIP-3607 Reject transactions from senders with deployed code
-    RejectCallerWithCode,
-    LackOfFundForGasLimit,
     CreateCollision,
     OverflowPayment,
     PrecompileError,
@@ -72,6 +66,51 @@ pub enum Return {
     CreateContractLimit,
     /// Error on created contract that begins with EF
     CreateContractWithEF,
+    /// EIP-3860: Limit and meter initcode. Initcode size limit exceeded.
+    CreateInitcodeSizeLimit,
+}
+
+/// Classification of [Return] that is returned from top level call or create.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SuccessOrHalt {
+    Success(Eval),
+    Revert,
+    Halt(Halt),
+    /// Database error is saved inside EVMData and returned to the user, other external errors
+    /// are returned as [crate::EVMError::FatalExternalError].
+    FatalExternalError,
+}
+
+impl From<Return> for SuccessOrHalt {
+    fn from(ret: Return) -> Self {
+        match ret {
+            // Continue is returned from precompiles and create when they are finished.
+            Return::Continue | Return::Return => Self::Success(Eval::Return),
+            Return::Stop => Self::Success(Eval::Stop),
+            Return::SelfDestruct => Self::Success(Eval::SelfDestruct),
+            Return::Revert => Self::Revert,
+            Return::CallTooDeep => Self::Halt(Halt::CallTooDeep),
+            Return::OutOfFund => Self::Halt(Halt::OutOfFund),
+            Return::OutOfGas => Self::Halt(Halt::OutOfGas),
+            Return::OpcodeNotFound => Self::Halt(Halt::OpcodeNotFound),
+            Return::CallNotAllowedInsideStatic => Self::Halt(Halt::CallNotAllowedInsideStatic),
+            Return::InvalidOpcode => Self::Halt(Halt::InvalidOpcode),
+            Return::InvalidJump => Self::Halt(Halt::InvalidJump),
+            Return::InvalidMemoryRange => Self::Halt(Halt::InvalidMemoryRange),
+            Return::NotActivated => Self::Halt(Halt::NotActivated),
+            Return::StackUnderflow => Self::Halt(Halt::StackUnderflow),
+            Return::StackOverflow => Self::Halt(Halt::StackOverflow),
+            Return::OutOfOffset => Self::Halt(Halt::OutOfOffset),
+            Return::FatalExternalError => Self::FatalExternalError,
+            Return::CreateCollision => Self::Halt(Halt::CreateCollision),
+            Return::OverflowPayment => Self::Halt(Halt::OverflowPayment),
+            Return::PrecompileError => Self::Halt(Halt::PrecompileError),
+            Return::NonceOverflow => Self::Halt(Halt::NonceOverflow),
+            Return::CreateContractLimit => Self::Halt(Halt::CreateContractLimit),
+            Return::CreateContractWithEF => Self::Halt(Halt::CreateContractWithEF),
+            Return::CreateInitcodeSizeLimit => Self::Halt(Halt::CreateInitcodeSizeLimit),
+        }
+    }
 }
 
 #[inline(always)]
@@ -142,6 +181,10 @@ pub fn eval<H: Host, S: Spec>(opcode: u8, interp: &mut Interpreter, host: &mut H
         opcode::PC => control::pc(interp),
         opcode::MSIZE => memory::msize(interp),
         opcode::JUMPDEST => control::jumpdest(interp),
+        opcode::TLOAD => host::tload::<H, S>(interp, host),
+        opcode::TSTORE => host::tstore::<H, S>(interp, host),
+        opcode::MCOPY => memory::mcopy::<S>(interp),
+        opcode::PUSH0 => stack::push0::<S>(interp),
         opcode::PUSH1 => stack::push::<1>(interp),
         opcode::PUSH2 => stack::push::<2>(interp),
         opcode::PUSH3 => stack::push::<3>(interp),
@@ -212,6 +255,8 @@ pub fn eval<H: Host, S: Spec>(opcode: u8, interp: &mut Interpreter, host: &mut H
         opcode::REVERT => control::revert::<S>(interp),
         opcode::INVALID => Return::InvalidOpcode,
         opcode::BASEFEE => host_env::basefee::<H, S>(interp, host),
+        opcode::BLOBHASH => host_env::blob_hash::<H, S>(interp, host),
+        opcode::BLOBBASEFEE => host_env::blob_basefee::<H, S>(interp, host),
         opcode::ORIGIN => host_env::origin(interp, host),
         opcode::CALLER => system::caller(interp),
         opcode::CALLVALUE => system::callvalue(interp),
@@ -225,7 +270,7 @@ pub fn eval<H: Host, S: Spec>(opcode: u8, interp: &mut Interpreter, host: &mut H
         opcode::COINBASE => host_env::coinbase(interp, host),
         opcode::TIMESTAMP => host_env::timestamp(interp, host),
         opcode::NUMBER => host_env::number(interp, host),
-        opcode::DIFFICULTY => host_env::difficulty(interp, host),
+        opcode::DIFFICULTY => host_env::difficulty::<H, S>(interp, host),
         opcode::GASLIMIT => host_env::gaslimit(interp, host),
         opcode::SLOAD => host::sload::<H, S>(interp, host),
         opcode::SSTORE => host::sstore::<H, S>(interp, host),
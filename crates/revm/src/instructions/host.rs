@@ -1,7 +1,7 @@
 use crate::{
     alloc::vec::Vec,
     gas::{self, COLD_ACCOUNT_ACCESS_COST, WARM_STORAGE_READ_COST},
-    interpreter::Interpreter,
+    interpreter::{Interpreter, MAX_INITCODE_SIZE},
     return_ok, return_revert, CallContext, CallInputs, CallScheme, CreateInputs, CreateScheme,
     Host, Return, Spec,
     SpecId::*,
@@ -158,6 +158,28 @@ pub fn sstore<H: Host, SPEC: Spec>(interp: &mut Interpreter, host: &mut H) -> Re
     interp.add_next_gas_block(interp.program_counter() - 1)
 }
 
+pub fn tstore<H: Host, SPEC: Spec>(interp: &mut Interpreter, host: &mut H) -> Return {
+    // gas!(interp, gas::WARM_STORAGE_READ_COST);
+    // EIP-1153: Transient storage opcodes
+    check!(SPEC::enabled(CANCUN));
+    check!(!SPEC::IS_STATIC_CALL);
+
+    pop!(interp, index, value);
+    host.tstore(interp.contract.address, index, value);
+    Return::Continue
+}
+
+pub fn tload<H: Host, SPEC: Spec>(interp: &mut Interpreter, host: &mut H) -> Return {
+    // gas!(interp, gas::WARM_STORAGE_READ_COST);
+    // EIP-1153: Transient storage opcodes
+    check!(SPEC::enabled(CANCUN));
+
+    pop!(interp, index);
+    let value = host.tload(interp.contract.address, index);
+    push!(interp, value);
+    Return::Continue
+}
+
 pub fn log<H: Host, SPEC: Spec>(interp: &mut Interpreter, n: u8, host: &mut H) -> Return {
     check!(!SPEC::IS_STATIC_CALL);
 
@@ -226,6 +248,20 @@ pub fn create<H: Host, SPEC: Spec>(
     let code = if len == 0 {
         Bytes::new()
     } else {
+        // EIP-3860: Limit and meter initcode
+        if SPEC::enabled(SHANGHAI) {
+            let max_initcode_size = host
+                .env()
+                .cfg
+                .limit_contract_code_size
+                .map(|limit| limit.saturating_mul(2))
+                .unwrap_or(MAX_INITCODE_SIZE);
+            if len > max_initcode_size {
+                return Return::CreateInitcodeSizeLimit;
+            }
+            gas!(interp, gas::initcode_cost(len));
+        }
+
         let code_offset = as_usize_or_fail!(code_offset, Return::OutOfGas);
         memory_resize!(interp, code_offset, len);
         Bytes::copy_from_slice(interp.memory.get_slice(code_offset, len))
@@ -1,5 +1,5 @@
 use crate::{interpreter::Interpreter, Host, Return, Spec, SpecId::*};
-use primitive_types::H256;
+use primitive_types::{H256, U256};
 
 pub fn chainid<H: Host, SPEC: Spec>(interp: &mut Interpreter, host: &mut H) -> Return {
     // gas!(interp, gas::BASE);
@@ -27,9 +27,15 @@ pub fn number<H: Host>(interp: &mut Interpreter, host: &mut H) -> Return {
     Return::Continue
 }
 
-pub fn difficulty<H: Host>(interp: &mut Interpreter, host: &mut H) -> Return {
+pub fn difficulty<H: Host, SPEC: Spec>(interp: &mut Interpreter, host: &mut H) -> Return {
     // gas!(interp, gas::BASE);
-    push!(interp, host.env().block.difficulty);
+    if SPEC::enabled(MERGE) {
+        // EIP-4399: Supplant DIFFICULTY opcode with PREVRANDAO
+        let prevrandao = host.env().block.prevrandao.unwrap_or_default();
+        push_h256!(interp, prevrandao);
+    } else {
+        push!(interp, host.env().block.difficulty);
+    }
     Return::Continue
 }
 
@@ -53,6 +59,34 @@ pub fn basefee<H: Host, SPEC: Spec>(interp: &mut Interpreter, host: &mut H) -> R
     Return::Continue
 }
 
+pub fn blob_hash<H: Host, SPEC: Spec>(interp: &mut Interpreter, host: &mut H) -> Return {
+    // gas!(interp, gas::VERYLOW);
+    // EIP-4844: Shard Blob Transactions
+    check!(SPEC::enabled(CANCUN));
+    pop!(interp, index);
+    let index = as_usize_saturated!(index);
+    let hash = host
+        .env()
+        .tx
+        .blob_hashes
+        .get(index)
+        .copied()
+        .unwrap_or_default();
+    push_h256!(interp, hash);
+    Return::Continue
+}
+
+pub fn blob_basefee<H: Host, SPEC: Spec>(interp: &mut Interpreter, host: &mut H) -> Return {
+    // gas!(interp, gas::BASE);
+    // EIP-7516: BLOBBASEFEE opcode
+    check!(SPEC::enabled(CANCUN));
+    push!(
+        interp,
+        U256::from(host.env().block.get_blob_gasprice().unwrap_or_default())
+    );
+    Return::Continue
+}
+
 pub fn origin<H: Host>(interp: &mut Interpreter, host: &mut H) -> Return {
     // gas!(interp, gas::BASE);
     let ret = H256::from(host.env().tx.caller);
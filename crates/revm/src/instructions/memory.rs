@@ -1,4 +1,4 @@
-use crate::{interpreter::Interpreter, Return};
+use crate::{gas, interpreter::Interpreter, Return, Spec, SpecId::*};
 use primitive_types::U256;
 
 pub fn mload(interp: &mut Interpreter) -> Return {
@@ -33,6 +33,23 @@ pub fn mstore8(interp: &mut Interpreter) -> Return {
     Return::Continue
 }
 
+pub fn mcopy<SPEC: Spec>(interp: &mut Interpreter) -> Return {
+    // EIP-5656: MCOPY - Memory copying instruction
+    check!(SPEC::enabled(CANCUN));
+    pop!(interp, dest, src, len);
+    let len = as_usize_or_fail!(len, Return::OutOfGas);
+    gas_or_fail!(interp, gas::verylowcopy_cost(len as u64));
+    if len == 0 {
+        return Return::Continue;
+    }
+    let dest = as_usize_or_fail!(dest, Return::OutOfGas);
+    let src = as_usize_or_fail!(src, Return::OutOfGas);
+    // resize memory to fit both regions, expansion cost is charged for the larger one.
+    memory_resize!(interp, core::cmp::max(dest, src), len);
+    interp.memory.copy(dest, src, len);
+    Return::Continue
+}
+
 pub fn msize(interp: &mut Interpreter) -> Return {
     // gas!(interp, gas::BASE);
     push!(interp, U256::from(interp.memory.effective_len()));
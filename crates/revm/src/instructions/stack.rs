@@ -1,10 +1,19 @@
-use crate::{interpreter::Interpreter, Return};
+use crate::{interpreter::Interpreter, Return, Spec, SpecId::*};
+use primitive_types::U256;
 
 pub fn pop(interp: &mut Interpreter) -> Return {
     // gas!(interp, gas::BASE);
     interp.stack.reduce_one()
 }
 
+pub fn push0<SPEC: Spec>(interp: &mut Interpreter) -> Return {
+    // gas!(interp, gas::BASE);
+    // EIP-3855: PUSH0 instruction
+    check!(SPEC::enabled(SHANGHAI));
+    push!(interp, U256::zero());
+    Return::Continue
+}
+
 pub fn push<const N: usize>(interp: &mut Interpreter) -> Return {
     // gas!(interp, gas::VERYLOW);
     let start = interp.instruction_pointer;
@@ -85,6 +85,13 @@ impl Memory {
         }
     }
 
+    /// Copy memory region from `src` to `dst`. Regions can overlap.
+    /// Offsets and length are expected to be already checked.
+    #[inline(always)]
+    pub fn copy(&mut self, dst: usize, src: usize, len: usize) {
+        self.data.copy_within(src..src + len, dst);
+    }
+
     /// Set memory from data. Our memory offset+len is expected to be correct but we
     /// are doing bound checks on data/data_offeset/len and zeroing parts that is not copied.
     #[inline(always)]
@@ -85,10 +85,10 @@ impl Stack {
     /// # Safety
     /// The caller is responsible to check length of array
     pub unsafe fn pop_unsafe(&mut self) -> U256 {
-        let mut len = self.data.len();
-        len -= 1;
+        let len = self.data.len() - 1;
+        let pop = *self.data.get_unchecked(len);
         self.data.set_len(len);
-        *self.data.get_unchecked(len)
+        pop
     }
 
     #[inline(always)]
@@ -136,13 +136,13 @@ impl Stack {
     /// # Safety
     /// The caller is responsible to check length of array
     pub unsafe fn pop2_unsafe(&mut self) -> (U256, U256) {
-        let mut len = self.data.len();
-        len -= 2;
-        self.data.set_len(len);
-        (
+        let len = self.data.len() - 2;
+        let pop = (
             *self.data.get_unchecked(len + 1),
             *self.data.get_unchecked(len),
-        )
+        );
+        self.data.set_len(len);
+        pop
     }
 
     #[inline(always)]
@@ -151,14 +151,14 @@ impl Stack {
     /// # Safety
     /// The caller is responsible to check length of array
     pub unsafe fn pop3_unsafe(&mut self) -> (U256, U256, U256) {
-        let mut len = self.data.len();
-        len -= 3;
-        self.data.set_len(len);
-        (
+        let len = self.data.len() - 3;
+        let pop = (
             *self.data.get_unchecked(len + 2),
             *self.data.get_unchecked(len + 1),
             *self.data.get_unchecked(len),
-        )
+        );
+        self.data.set_len(len);
+        pop
     }
 
     #[inline(always)]
@@ -167,15 +167,15 @@ impl Stack {
     /// # Safety
     /// The caller is responsible to check length of array
     pub unsafe fn pop4_unsafe(&mut self) -> (U256, U256, U256, U256) {
-        let mut len = self.data.len();
-        len -= 4;
-        self.data.set_len(len);
-        (
+        let len = self.data.len() - 4;
+        let pop = (
             *self.data.get_unchecked(len + 3),
             *self.data.get_unchecked(len + 2),
             *self.data.get_unchecked(len + 1),
             *self.data.get_unchecked(len),
-        )
+        );
+        self.data.set_len(len);
+        pop
     }
 
     #[inline]
@@ -222,8 +222,9 @@ impl Stack {
         } else {
             // Safety: check for out of bounds is done above and it makes this safe to do.
             unsafe {
-                *self.data.get_unchecked_mut(len) = *self.data.get_unchecked(len - N);
-                self.data.set_len(len + 1);
+                let value = *self.data.get_unchecked(len - N);
+                // Capacity is STACK_LIMIT so push never reallocates.
+                self.data.push(value);
             }
             Return::Continue
         }
@@ -306,3 +307,42 @@ impl Stack {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Stack;
+    use primitive_types::U256;
+
+    #[test]
+    fn pop_unsafe_returns_values_from_top() {
+        let mut stack = Stack::new();
+        for value in 1..=10u64 {
+            stack.push(U256::from(value)).unwrap();
+        }
+        unsafe {
+            assert_eq!(stack.pop_unsafe(), U256::from(10));
+            assert_eq!(stack.pop2_unsafe(), (U256::from(9), U256::from(8)));
+            assert_eq!(
+                stack.pop3_unsafe(),
+                (U256::from(7), U256::from(6), U256::from(5))
+            );
+            assert_eq!(
+                stack.pop4_unsafe(),
+                (U256::from(4), U256::from(3), U256::from(2), U256::from(1))
+            );
+        }
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn dup_copies_value_to_top() {
+        let mut stack = Stack::new();
+        stack.push(U256::from(1)).unwrap();
+        stack.push(U256::from(2)).unwrap();
+        stack.dup::<2>();
+        assert_eq!(
+            stack.data(),
+            &vec![U256::from(1), U256::from(2), U256::from(1)]
+        );
+    }
+}
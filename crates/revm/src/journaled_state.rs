@@ -1,7 +1,7 @@
 use crate::{interpreter::bytecode::Bytecode, models::SelfDestructResult, Return, KECCAK_EMPTY};
 use alloc::{vec, vec::Vec};
 use core::mem::{self};
-use hashbrown::{hash_map::Entry, HashMap as Map};
+use hashbrown::{hash_map::Entry, HashMap as Map, HashSet};
 use primitive_types::{H160, U256};
 
 use crate::{db::Database, AccountInfo, Log};
@@ -11,6 +11,8 @@ use crate::{db::Database, AccountInfo, Log};
 pub struct JournaledState {
     /// Current state.
     pub state: State,
+    /// EIP-1153 transient storage that is discarded after every transaction.
+    pub transient_storage: TransientStorage,
     /// logs
     pub logs: Vec<Log>,
     /// how deep are we in call stack.
@@ -21,13 +23,15 @@ pub struct JournaledState {
     /// so we need to take care of that difference. Set this to false if you are handling
     /// legacy transactions
     pub is_before_spurious_dragon: bool,
-    /// It is assumed that precompiles start from 0x1 address and spand next N addresses.
-    /// we are using that assumption here
-    pub num_of_precompiles: usize,
+    /// Addresses of precompiles. They are always warm (EIP-2929) and can be
+    /// at any address, not only at the beginning of address space.
+    pub precompile_addresses: HashSet<H160>,
 }
 
 pub type State = Map<H160, Account>;
 pub type Storage = Map<U256, StorageSlot>;
+/// Transient storage slots keyed by account address and slot index.
+pub type TransientStorage = Map<(H160, U256), U256>;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
@@ -92,6 +96,13 @@ impl StorageSlot {
         }
     }
 
+    pub fn new_changed(original_value: U256, present_value: U256) -> Self {
+        Self {
+            original_value,
+            present_value,
+        }
+    }
+
     /// Returns true if the present value differs from the original value
     pub fn is_changed(&self) -> bool {
         self.original_value != self.present_value
@@ -150,6 +161,14 @@ pub enum JournalEntry {
     /// Action: Account code changed
     /// Revert: Revert to previous bytecode.
     CodeChange { address: H160, had_code: Bytecode },
+    /// It is used to track changes of transient storage (EIP-1153).
+    /// Action: Transient storage changed.
+    /// Revert: Revert to previous value.
+    TransientStorageChange {
+        address: H160,
+        key: U256,
+        had_value: U256,
+    },
 }
 
 /// SubRoutine checkpoint that will help us to go back from this
@@ -159,19 +178,20 @@ pub struct JournalCheckpoint {
 }
 
 impl JournaledState {
-    pub fn new(num_of_precompiles: usize) -> JournaledState {
+    pub fn new(precompile_addresses: HashSet<H160>) -> JournaledState {
         Self {
             state: Map::new(),
+            transient_storage: Map::new(),
             logs: Vec::new(),
             journal: vec![vec![]],
             depth: 0,
             is_before_spurious_dragon: false,
-            num_of_precompiles,
+            precompile_addresses,
         }
     }
 
-    pub fn new_legacy(num_of_precompiles: usize) -> JournaledState {
-        let mut journal = Self::new(num_of_precompiles);
+    pub fn new_legacy(precompile_addresses: HashSet<H160>) -> JournaledState {
+        let mut journal = Self::new(precompile_addresses);
         journal.is_before_spurious_dragon = true;
         journal
     }
@@ -203,6 +223,7 @@ impl JournaledState {
             .collect();
 
         let logs = mem::take(&mut self.logs);
+        self.transient_storage = Map::new();
         self.journal = vec![vec![]];
         self.depth = 0;
         (state, logs)
@@ -252,35 +273,38 @@ impl JournaledState {
         Some(account.info.nonce)
     }
 
+    /// Transfer balance between two accounts.
+    ///
+    /// Returns `Ok(None)` on success, `Ok(Some(Return))` if transfer could not be done
+    /// (not enough funds or overflow) and error if database failed to load accounts.
     pub fn transfer<DB: Database>(
         &mut self,
         from: &H160,
         to: &H160,
         balance: U256,
         db: &mut DB,
-    ) -> Result<(bool, bool), Return> {
+    ) -> Result<Option<Return>, DB::Error> {
         // load accounts
-        let (_, from_is_cold) = self
-            .load_account(*from, db)
-            .map_err(|_| Return::FatalExternalError)?;
-
-        let (_, to_is_cold) = self
-            .load_account(*to, db)
-            .map_err(|_| Return::FatalExternalError)?;
+        self.load_account(*from, db)?;
+        self.load_account(*to, db)?;
 
         // sub balance from
         let from_account = &mut self.state.get_mut(from).unwrap();
         Self::touch_account(self.journal.last_mut().unwrap(), from, from_account);
         let from_balance = &mut from_account.info.balance;
-        *from_balance = from_balance.checked_sub(balance).ok_or(Return::OutOfFund)?;
+        *from_balance = match from_balance.checked_sub(balance) {
+            Some(from_balance) => from_balance,
+            None => return Ok(Some(Return::OutOfFund)),
+        };
 
         // add balance to
         let to_account = &mut self.state.get_mut(to).unwrap();
         Self::touch_account(self.journal.last_mut().unwrap(), to, to_account);
         let to_balance = &mut to_account.info.balance;
-        *to_balance = to_balance
-            .checked_add(balance)
-            .ok_or(Return::OverflowPayment)?;
+        *to_balance = match to_balance.checked_add(balance) {
+            Some(to_balance) => to_balance,
+            None => return Ok(Some(Return::OverflowPayment)),
+        };
         // Overflow of U256 balance is not possible to happen on mainnet. We dont bother to return funds from from_acc.
 
         self.journal
@@ -292,7 +316,7 @@ impl JournaledState {
                 balance,
             });
 
-        Ok((from_is_cold, to_is_cold))
+        Ok(None)
     }
 
     /// return if it has collision of addresses
@@ -341,6 +365,7 @@ impl JournaledState {
 
     fn journal_revert(
         state: &mut State,
+        transient_storage: &mut TransientStorage,
         journal_entries: Vec<JournalEntry>,
         is_spurious_dragon_enabled: bool,
     ) {
@@ -400,6 +425,20 @@ impl JournaledState {
                     acc.info.code_hash = had_code.hash();
                     acc.info.code = Some(had_code);
                 }
+                JournalEntry::TransientStorageChange {
+                    address,
+                    key,
+                    had_value,
+                } => {
+                    let tkey = (address, key);
+                    if had_value.is_zero() {
+                        // if previous value is zero, remove it
+                        transient_storage.remove(&tkey);
+                    } else {
+                        // if not zero, reinsert old value to transient storage.
+                        transient_storage.insert(tkey, had_value);
+                    }
+                }
             }
         }
     }
@@ -421,6 +460,7 @@ impl JournaledState {
     pub fn checkpoint_revert(&mut self, checkpoint: JournalCheckpoint) {
         let is_spurious_dragon_enabled = !self.is_before_spurious_dragon;
         let state = &mut self.state;
+        let transient_storage = &mut self.transient_storage;
         self.depth -= 1;
         // iterate over last N journals sets and revert our global state
         let leng = self.journal.len();
@@ -428,7 +468,14 @@ impl JournaledState {
             .iter_mut()
             .rev()
             .take(leng - checkpoint.journal_i)
-            .for_each(|cs| Self::journal_revert(state, mem::take(cs), is_spurious_dragon_enabled));
+            .for_each(|cs| {
+                Self::journal_revert(
+                    state,
+                    transient_storage,
+                    mem::take(cs),
+                    is_spurious_dragon_enabled,
+                )
+            });
 
         self.logs.truncate(checkpoint.log_i);
         self.journal.truncate(checkpoint.journal_i);
@@ -496,7 +543,7 @@ impl JournaledState {
                     .push(JournalEntry::AccountLoaded { address });
 
                 // precompiles are hot loaded so we need to take that into account
-                let is_cold = !is_precompile(address, self.num_of_precompiles);
+                let is_cold = !self.precompile_addresses.contains(&address);
 
                 (vac.insert(account), is_cold)
             }
@@ -607,69 +654,98 @@ impl JournaledState {
         Ok((slot.original_value, present, new, is_cold))
     }
 
+    /// Read transient storage tied to the account.
+    ///
+    /// EIP-1153: Transient storage opcodes
+    pub fn tload(&mut self, address: H160, key: U256) -> U256 {
+        self.transient_storage
+            .get(&(address, key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Store transient storage tied to the account.
+    ///
+    /// If values is different add entry to the journal
+    /// so that old state can be reverted if that action is needed.
+    ///
+    /// EIP-1153: Transient storage opcodes
+    pub fn tstore(&mut self, address: H160, key: U256, new: U256) {
+        let had_value = if new.is_zero() {
+            // if new values is zero, remove entry from transient storage.
+            // if previous values was some insert it inside journal.
+            // If it is none nothing should be inserted.
+            self.transient_storage.remove(&(address, key))
+        } else {
+            // insert values
+            let previous_value = self
+                .transient_storage
+                .insert((address, key), new)
+                .unwrap_or_default();
+
+            // check if previous value is same
+            if previous_value != new {
+                // if it is different, insert previous values inside journal.
+                Some(previous_value)
+            } else {
+                None
+            }
+        };
+
+        if let Some(had_value) = had_value {
+            // insert in journal only if value was changed.
+            self.journal
+                .last_mut()
+                .unwrap()
+                .push(JournalEntry::TransientStorageChange {
+                    address,
+                    key,
+                    had_value,
+                });
+        }
+    }
+
     /// push log into subroutine
     pub fn log(&mut self, log: Log) {
         self.logs.push(log);
     }
 }
 
-fn is_precompile(address: H160, num_of_precompiles: usize) -> bool {
-    if !address[..18].iter().all(|i| *i == 0) {
-        return false;
-    }
-    let num = u16::from_be_bytes([address[18], address[19]]);
-    num.wrapping_sub(1) < num_of_precompiles as u16
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
-    fn test_is_precompile() {
-        assert_eq!(
-            is_precompile(
-                H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
-                3
-            ),
-            false,
-            "Zero is not precompile"
-        );
-
-        assert_eq!(
-            is_precompile(
-                H160([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9]),
-                3
-            ),
-            false,
-            "0x100..0 is not precompile"
-        );
-
-        assert_eq!(
-            is_precompile(
-                H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4]),
-                3
-            ),
-            false,
-            "0x000..4 is not precompile"
-        );
-
-        assert_eq!(
-            is_precompile(
-                H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
-                3
-            ),
-            true,
-            "0x00..01 is precompile"
-        );
-
-        assert_eq!(
-            is_precompile(
-                H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3]),
-                3
-            ),
-            true,
-            "0x000..3 is precompile"
-        );
+    fn test_precompiles_are_warm() {
+        let precompile = H160::from_low_u64_be(0x4200);
+        let mut journal = JournaledState::new([precompile].into_iter().collect());
+        let mut db = crate::InMemoryDB::default();
+
+        let (_, is_cold) = journal.load_account(precompile, &mut db).unwrap();
+        assert!(!is_cold, "Precompile at non contiguous address is warm");
+        let (_, is_cold) = journal
+            .load_account(H160::from_low_u64_be(1), &mut db)
+            .unwrap();
+        assert!(is_cold, "0x00..01 is not precompile");
+    }
+
+    #[test]
+    fn test_transient_storage_revert() {
+        let mut journal = JournaledState::new(HashSet::new());
+        let address = H160::from_low_u64_be(1);
+        let key = U256::one();
+
+        journal.tstore(address, key, U256::from(1));
+        let checkpoint = journal.checkpoint();
+        journal.tstore(address, key, U256::from(2));
+        journal.tstore(address, U256::from(2), U256::from(3));
+        assert_eq!(journal.tload(address, key), U256::from(2));
+
+        journal.checkpoint_revert(checkpoint);
+        assert_eq!(journal.tload(address, key), U256::from(1));
+        assert_eq!(journal.tload(address, U256::from(2)), U256::zero());
+
+        journal.finalize();
+        assert_eq!(journal.tload(address, key), U256::zero());
     }
 }
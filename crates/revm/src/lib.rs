@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 //#![no_std]
 
+mod block;
 pub mod db;
 mod evm;
 mod evm_impl;
@@ -10,26 +11,48 @@ mod instructions;
 mod interpreter;
 mod journaled_state;
 mod models;
+mod precompile;
+mod prestate;
+#[cfg(feature = "revert_reason")]
+mod revert;
 mod specification;
+#[cfg(test)]
+mod test_utils;
+mod transaction;
 
 pub use evm_impl::{create2_address, create_address, EVMData, Host};
 
 pub type DummyStateDB = InMemoryDB;
 
+pub use block::{
+    apply_block_rewards, apply_dao_fork, apply_withdrawals, block_bloom, block_reward, BlockError,
+    Bloom, Receipt, Withdrawal, BLOOM_SIZE, DAO_DRAIN_LIST, DAO_HARDFORK_BENEFICIARY,
+    DAO_HARDFORK_BLOCK, GWEI_TO_WEI,
+};
 pub use db::{Database, DatabaseCommit, InMemoryDB};
-pub use evm::{evm_inner, new, EVM};
+pub use evm::{evm_inner, new, ACCESS_LIST_MAX_ITERATIONS, EVM};
 pub use gas::Gas;
-pub use inspector::{GasInspector, Inspector, NoOpInspector};
+pub use inspector::{
+    AccessListInspector, CallFrame, CallKind, CallTracer, CallTracerConfig, GasInspector,
+    GethTrace, Inspector, NoOpInspector, StructLog, StructLogger, StructLoggerConfig,
+};
+#[cfg(feature = "std")]
+pub use inspector::{PrecompileProfiler, PrecompileStats};
 pub use instructions::{
     opcode::{self, spec_opcode_gas, OpCode, OPCODE_JUMPMAP},
-    Return,
+    Return, SuccessOrHalt,
 };
 pub use interpreter::{
     Bytecode, BytecodeLocked, BytecodeState, Contract, Interpreter, Memory, Stack,
 };
 pub use journaled_state::{Account, JournalEntry, JournaledState};
 pub use models::*;
+pub use precompile::{PrecompileHost, StatefulPrecompile, StatefulPrecompiles};
+pub use prestate::{prestate, state_diff, AccountSnapshot, StateDiff};
+#[cfg(feature = "revert_reason")]
+pub use revert::{CustomErrorDecoder, RevertDecoder, RevertReason, ERROR_SELECTOR, PANIC_SELECTOR};
 pub use specification::*;
+pub use transaction::{SignedTransaction, TransactionDecodeError, TxType};
 
 extern crate alloc;
 
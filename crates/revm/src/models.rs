@@ -1,6 +1,6 @@
 use core::cmp::min;
 
-use crate::{alloc::vec::Vec, interpreter::bytecode::Bytecode, Return, SpecId};
+use crate::{alloc::vec::Vec, interpreter::bytecode::Bytecode, SpecId};
 use bytes::Bytes;
 use primitive_types::{H160, H256, U256};
 
@@ -99,7 +99,7 @@ pub struct CreateInputs {
 
 pub struct CreateData {}
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransactTo {
     Call(H160),
@@ -112,7 +112,7 @@ impl TransactTo {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransactOut {
     None,
@@ -195,9 +195,15 @@ pub struct BlockEnv {
     pub coinbase: H160,
     pub timestamp: U256,
     pub difficulty: U256,
+    /// Prevrandao is used after Paris (aka TheMerge) instead of the difficulty value.
+    /// NOTE: prevrandao can be found in block in place of mix_hash field.
+    pub prevrandao: Option<H256>,
     /// basefee is added in EIP1559 London upgrade
     pub basefee: U256,
     pub gas_limit: U256,
+    /// Excess blob gas of the block, added in EIP-4844 Cancun upgrade.
+    /// It is used to calculate blob gas price.
+    pub excess_blob_gas: Option<u64>,
 }
 
 #[derive(Clone, Debug)]
@@ -215,6 +221,9 @@ pub struct TxEnv {
     pub chain_id: Option<u64>,
     pub nonce: Option<u64>,
     pub access_list: Vec<(H160, Vec<U256>)>,
+    /// Versioned hashes of blobs carried by transaction, added in EIP-4844.
+    /// Accessible with BLOBHASH opcode.
+    pub blob_hashes: Vec<H256>,
 }
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
@@ -232,7 +241,12 @@ pub struct CfgEnv {
     pub perf_analyse_created_bytecodes: AnalysisKind,
     /// If some it will effects EIP-170: Contract code size limit. Usefull to increase this because of tests.
     /// By default it is 0x6000 (~25kb).
+    /// EIP-3860 initcode size limit is set to double of this value.
     pub limit_contract_code_size: Option<usize>,
+    /// Skip check of transaction nonce and chain id against caller account and `chain_id`.
+    /// Useful for simulations (eth_call like requests) where nonce is not known or not relevant.
+    /// Default: false
+    pub disable_nonce_and_chain_id_check: bool,
     /// A hard memory limit in bytes beyond which [Memory] cannot be resized.
     ///
     /// In cases where the gas limit may be extraordinarily high, it is recommended to set this to
@@ -259,6 +273,7 @@ impl Default for CfgEnv {
             perf_all_precompiles_have_balance: false,
             perf_analyse_created_bytecodes: Default::default(),
             limit_contract_code_size: None,
+            disable_nonce_and_chain_id_check: false,
             #[cfg(feature = "memory_limit")]
             memory_limit: 2u64.pow(32) - 1,
         }
@@ -273,9 +288,64 @@ impl Default for BlockEnv {
             coinbase: H160::zero(),
             timestamp: U256::one(),
             difficulty: U256::zero(),
+            prevrandao: Some(H256::zero()),
             basefee: U256::zero(),
+            excess_blob_gas: None,
+        }
+    }
+}
+
+impl BlockEnv {
+    /// Returns blob gas price if `excess_blob_gas` is set.
+    ///
+    /// See EIP-4844: Shard Blob Transactions
+    pub fn get_blob_gasprice(&self) -> Option<u128> {
+        self.excess_blob_gas.map(calc_blob_gasprice)
+    }
+}
+
+/// Minimum gas price for data blobs. EIP-4844
+pub const MIN_BLOB_GASPRICE: u64 = 1;
+/// Controls the maximum rate of change for blob gas price. EIP-4844
+pub const BLOB_GASPRICE_UPDATE_FRACTION: u64 = 3338477;
+
+/// Calculates the blob gas price from excess blob gas.
+///
+/// See EIP-4844: Shard Blob Transactions
+pub fn calc_blob_gasprice(excess_blob_gas: u64) -> u128 {
+    fake_exponential(
+        MIN_BLOB_GASPRICE,
+        excess_blob_gas,
+        BLOB_GASPRICE_UPDATE_FRACTION,
+    )
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using Taylor expansion.
+/// Result is saturated to `u128::MAX`.
+///
+/// See EIP-4844: Shard Blob Transactions
+pub fn fake_exponential(factor: u64, numerator: u64, denominator: u64) -> u128 {
+    assert_ne!(denominator, 0, "attempt to divide by zero");
+    let factor = U256::from(factor);
+    let numerator = U256::from(numerator);
+    let denominator = U256::from(denominator);
+    // bound of output before division, it can't overflow U256 when multiplied by numerator.
+    let max_output = U256::from(u128::MAX) * denominator;
+
+    let mut i = U256::one();
+    let mut output = U256::zero();
+    let mut numerator_accum = factor * denominator;
+    while !numerator_accum.is_zero() {
+        output += numerator_accum;
+        if output > max_output {
+            return u128::MAX;
         }
+
+        // Denominator is asserted as not zero at the start of the function.
+        numerator_accum = (numerator_accum * numerator) / (denominator * i);
+        i += U256::one();
     }
+    (output / denominator).as_u128()
 }
 
 impl Default for TxEnv {
@@ -291,6 +361,7 @@ impl Default for TxEnv {
             chain_id: None,
             nonce: None,
             access_list: Vec::new(),
+            blob_hashes: Vec::new(),
         }
     }
 }
@@ -364,6 +435,31 @@ pub(crate) mod serde_hex_bytes {
         .map_err(|e| serde::de::Error::custom(e.to_string()))
     }
 }
+/// Serde functions to serde u64 as hex quantity string
+#[cfg(feature = "with-serde")]
+pub(crate) mod serde_hex_u64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(x: &u64, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(&format!("{:#x}", x))
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(d)?;
+        if let Some(value) = value.strip_prefix("0x") {
+            u64::from_str_radix(value, 16)
+        } else {
+            value.parse()
+        }
+        .map_err(|e| serde::de::Error::custom(e.to_string()))
+    }
+}
 /// Serde functions to serde an Option [bytes::Bytes] hex string
 #[cfg(feature = "with-serde")]
 pub(crate) mod serde_hex_bytes_opt {
@@ -408,23 +504,197 @@ pub(crate) mod serde_hex_bytes_opt {
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct ExecutionResult {
-    pub exit_reason: Return,
-    pub out: TransactOut,
-    pub gas_used: u64,
-    pub gas_refunded: u64,
-    pub logs: Vec<Log>,
+/// Errors that can happen during transaction execution and are not part of [ExecutionResult].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EVMError<DBError> {
+    /// Transaction is not valid and it is not executed.
+    Transaction(InvalidTransaction),
+    /// Error returned from [crate::Database] while executing transaction.
+    Database(DBError),
+    /// Execution was stopped with [crate::Return::FatalExternalError] by [crate::Inspector] or
+    /// stateful precompile without database error.
+    FatalExternalError,
+}
+
+/// Reasons why transaction is rejected before it is executed.
+/// Rejected transaction does not change the state and it can't be included in the block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InvalidTransaction {
+    GasMaxFeeGreaterThanPriorityFee,
+    GasPriceLessThenBasefee,
+    CallerGasLimitMoreThenBlock,
+    /// Transaction gas limit is more then gas that is left in the block.
+    BlockGasLimitReached,
+    /// Initial gas (intrinsic gas and access list) is more then transaction gas limit.
+    CallGasCostMoreThenGasLimit,
+    /// EIP-3607 Reject transactions from senders with deployed code
+    RejectCallerWithCode,
+    /// Caller does not have enough balance to pay for `gas_limit * gas_price`.
+    LackOfFundForGasLimit,
+    /// Caller does not have enough balance to transfer value.
+    LackOfFundForValue,
+    /// `gas_limit * gas_price` overflows U256.
+    OverflowPaymentInTransaction,
+    /// EIP-2681: Limit account nonce to 2^64-1
+    NonceOverflowInTransaction,
+    /// Transaction nonce is higher then nonce of caller account.
+    NonceTooHigh,
+    /// Transaction nonce is lower then nonce of caller account.
+    NonceTooLow,
+    /// Transaction chain id does not match chain id from config.
+    InvalidChainId,
+    /// EIP-3860: Limit and meter initcode. Initcode of create transaction is too large.
+    CreateInitcodeSizeLimit,
+}
+
+/// Reason of successful execution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Eval {
+    Stop,
+    Return,
+    SelfDestruct,
+}
+
+/// Reason why execution halted. All gas given to the transaction is consumed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Halt {
+    OutOfGas,
+    OpcodeNotFound,
+    CallNotAllowedInsideStatic,
+    InvalidOpcode,
+    InvalidJump,
+    InvalidMemoryRange,
+    NotActivated,
+    StackUnderflow,
+    StackOverflow,
+    OutOfOffset,
+    CreateCollision,
+    OverflowPayment,
+    PrecompileError,
+    NonceOverflow,
+    /// Create init code exceeds limit (runtime).
+    CreateContractLimit,
+    /// Error on created contract that begins with EF
+    CreateContractWithEF,
+    /// EIP-3860: Limit and meter initcode. Initcode size limit exceeded.
+    CreateInitcodeSizeLimit,
+    CallTooDeep,
+    OutOfFund,
+}
+
+/// Result of transaction execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExecutionResult {
+    /// Returned successfully
+    Success {
+        reason: Eval,
+        gas_used: u64,
+        gas_refunded: u64,
+        logs: Vec<Log>,
+        output: TransactOut,
+    },
+    /// Reverted by `REVERT` opcode that doesn't spend all gas.
+    Revert {
+        gas_used: u64,
+        #[cfg_attr(feature = "with-serde", serde(with = "serde_hex_bytes"))]
+        output: Bytes,
+    },
+    /// Reverted for various reasons and spend all gas.
+    Halt { reason: Halt, gas_used: u64 },
+}
+
+/// Result of [crate::EVM::estimate_gas].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EstimateGasResult {
+    /// Minimal gas limit with which transaction succeeds.
+    Success { gas_limit: u64 },
+    /// Transaction fails even with the highest allowed gas limit. Contains result of that execution
+    /// or [Halt::OutOfGas] if the limit is below intrinsic gas.
+    Failure(ExecutionResult),
 }
 
 impl ExecutionResult {
-    pub fn new_with_reason(reason: Return) -> ExecutionResult {
-        ExecutionResult {
-            exit_reason: reason,
-            out: TransactOut::None,
-            gas_used: 0,
-            gas_refunded: 0,
-            logs: Vec::new(),
+    /// Returns if transaction execution is successful.
+    /// 1 indicates success, 0 indicates revert.
+    /// https://eips.ethereum.org/EIPS/eip-658
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+
+    /// Return logs, if execution is not successful, function will return empty vec.
+    pub fn logs(&self) -> &[Log] {
+        match self {
+            Self::Success { logs, .. } => logs,
+            _ => &[],
+        }
+    }
+
+    /// Returns output of successful execution or revert data.
+    pub fn output(&self) -> Option<&Bytes> {
+        match self {
+            Self::Success {
+                output: TransactOut::Call(output) | TransactOut::Create(output, _),
+                ..
+            } => Some(output),
+            Self::Revert { output, .. } => Some(output),
+            _ => None,
+        }
+    }
+
+    /// Decoded reason of reverted transaction. Use [crate::RevertDecoder::default] if only
+    /// `Error(string)` and `Panic(uint256)` need to be decoded.
+    #[cfg(feature = "revert_reason")]
+    pub fn revert_reason(&self, decoder: &crate::RevertDecoder) -> Option<crate::RevertReason> {
+        match self {
+            Self::Revert { output, .. } => decoder.decode(output),
+            _ => None,
         }
     }
+
+    /// Returns gas used by the transaction.
+    pub fn gas_used(&self) -> u64 {
+        match *self {
+            Self::Success { gas_used, .. }
+            | Self::Revert { gas_used, .. }
+            | Self::Halt { gas_used, .. } => gas_used,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fake_exponential() {
+        for (factor, numerator, denominator, expected) in [
+            (1, 0, 1, 1),
+            (38493, 0, 1000, 38493),
+            (0, 1234, 2345, 0),
+            (1, 2, 1, 6),
+            (1, 4, 2, 6),
+            (1, 3, 1, 16),
+            (1, 6, 2, 18),
+            (1, 8, 2, 50),
+            (1, 9, 2, 84),
+            (1, 10, 1, 21907),
+            (1, 11, 1, 59340),
+        ] {
+            assert_eq!(fake_exponential(factor, numerator, denominator), expected);
+        }
+        assert_eq!(calc_blob_gasprice(5 * BLOB_GASPRICE_UPDATE_FRACTION), 148);
+    }
+
+    #[test]
+    fn fake_exponential_saturates() {
+        assert_eq!(fake_exponential(1, 100, 1), u128::MAX);
+        assert_eq!(calc_blob_gasprice(u64::MAX), u128::MAX);
+        assert_eq!(fake_exponential(u64::MAX, u64::MAX, 1), u128::MAX);
+    }
 }
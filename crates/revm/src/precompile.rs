@@ -0,0 +1,242 @@
+use crate::{db::Database, evm_impl::EVMData, CallContext, Env, Log, Return};
+use alloc::sync::Arc;
+use hashbrown::HashMap as Map;
+use primitive_types::{H160, U256};
+use revm_precompiles::PrecompileResult;
+
+/// State access given to [StatefulPrecompile] while it is executed.
+///
+/// It mirrors [crate::Host] but it is object safe so that precompiles does not depend on
+/// the database type. Functions that touch the database return `None` if database returned
+/// an error, the error is saved and the transaction is going to fail with [crate::EVMError::Database].
+pub trait PrecompileHost {
+    fn env(&mut self) -> &mut Env;
+    /// load account. Returns (is_cold,is_new_account)
+    fn load_account(&mut self, address: H160) -> Option<(bool, bool)>;
+    /// Get balance of address.
+    fn balance(&mut self, address: H160) -> Option<(U256, bool)>;
+    /// Get storage value of address at index. Account is loaded if it is not already.
+    fn sload(&mut self, address: H160, index: U256) -> Option<(U256, bool)>;
+    /// Set storage value of address at index. Returns (original, present, new, is_cold).
+    /// Account is loaded if it is not already.
+    fn sstore(
+        &mut self,
+        address: H160,
+        index: U256,
+        value: U256,
+    ) -> Option<(U256, U256, U256, bool)>;
+    /// Transfer value between accounts. Returns error if transfer is not possible.
+    fn transfer(&mut self, from: H160, to: H160, value: U256) -> Option<Return>;
+    /// Create a log. Logs are reverted together with the call.
+    fn log(&mut self, log: Log);
+}
+
+impl<'a, DB: Database> PrecompileHost for EVMData<'a, DB> {
+    fn env(&mut self) -> &mut Env {
+        self.env
+    }
+
+    fn load_account(&mut self, address: H160) -> Option<(bool, bool)> {
+        self.journaled_state
+            .load_account_exist(address, self.db)
+            .map_err(|e| self.error = Some(e))
+            .ok()
+    }
+
+    fn balance(&mut self, address: H160) -> Option<(U256, bool)> {
+        let error = &mut self.error;
+        self.journaled_state
+            .load_account(address, self.db)
+            .map_err(|e| *error = Some(e))
+            .ok()
+            .map(|(acc, is_cold)| (acc.info.balance, is_cold))
+    }
+
+    fn sload(&mut self, address: H160, index: U256) -> Option<(U256, bool)> {
+        // Journaled state expects account to be loaded before its storage is accessed.
+        self.journaled_state
+            .load_account(address, self.db)
+            .map_err(|e| self.error = Some(e))
+            .ok()?;
+        self.journaled_state
+            .sload(address, index, self.db)
+            .map_err(|e| self.error = Some(e))
+            .ok()
+    }
+
+    fn sstore(
+        &mut self,
+        address: H160,
+        index: U256,
+        value: U256,
+    ) -> Option<(U256, U256, U256, bool)> {
+        self.journaled_state
+            .load_account(address, self.db)
+            .map_err(|e| self.error = Some(e))
+            .ok()?;
+        // Only touched accounts are part of the final state.
+        self.journaled_state.touch(&address);
+        self.journaled_state
+            .sstore(address, index, value, self.db)
+            .map_err(|e| self.error = Some(e))
+            .ok()
+    }
+
+    fn transfer(&mut self, from: H160, to: H160, value: U256) -> Option<Return> {
+        match self.journaled_state.transfer(&from, &to, value, self.db) {
+            Ok(ret) => ret,
+            Err(err) => {
+                self.error = Some(err);
+                Some(Return::FatalExternalError)
+            }
+        }
+    }
+
+    fn log(&mut self, log: Log) {
+        self.journaled_state.log(log);
+    }
+}
+
+/// Precompile that can read and modify the state.
+///
+/// It is called inside the call checkpoint, so if it returns an error or runs out of gas all
+/// changes done through [PrecompileHost] are reverted. Logs from returned
+/// [revm_precompiles::PrecompileOutput] are added to the state same as for standard precompiles.
+pub trait StatefulPrecompile: Send + Sync {
+    fn call(
+        &self,
+        host: &mut dyn PrecompileHost,
+        input: &[u8],
+        gas_limit: u64,
+        context: &CallContext,
+        is_static: bool,
+    ) -> PrecompileResult;
+}
+
+impl<F> StatefulPrecompile for F
+where
+    F: Fn(&mut dyn PrecompileHost, &[u8], u64, &CallContext, bool) -> PrecompileResult
+        + Send
+        + Sync,
+{
+    fn call(
+        &self,
+        host: &mut dyn PrecompileHost,
+        input: &[u8],
+        gas_limit: u64,
+        context: &CallContext,
+        is_static: bool,
+    ) -> PrecompileResult {
+        self(host, input, gas_limit, context, is_static)
+    }
+}
+
+/// Stateful precompiles indexed by their address.
+pub type StatefulPrecompiles = Map<H160, Arc<dyn StatefulPrecompile>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExecutionResult, InMemoryDB, TransactOut, TransactTo};
+    use revm_precompiles::{Precompile, PrecompileOutput, Precompiles};
+
+    #[test]
+    fn stateful_precompile_writes_storage() {
+        let address = H160::from_low_u64_be(0x4200);
+        let mut evm = crate::new();
+        evm.database(InMemoryDB::default());
+        evm.stateful_precompile(
+            address,
+            |host: &mut dyn PrecompileHost,
+             input: &[u8],
+             _gas_limit: u64,
+             context: &CallContext,
+             is_static: bool|
+             -> PrecompileResult {
+                assert!(!is_static);
+                host.sstore(context.address, U256::zero(), U256::from(input.len()))
+                    .unwrap();
+                Ok(PrecompileOutput::without_logs(100, input.to_vec()))
+            },
+        );
+        evm.env.tx.transact_to = TransactTo::Call(address);
+        evm.env.tx.data = bytes::Bytes::from_static(&[1, 2, 3]);
+
+        let (result, state) = evm.transact().unwrap();
+        match result {
+            ExecutionResult::Success { gas_used, .. } => {
+                assert_eq!(gas_used, 21_000 + 16 * 3 + 100)
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(
+            state[&address].storage[&U256::zero()].present_value(),
+            U256::from(3)
+        );
+    }
+
+    #[test]
+    fn stateful_precompile_accesses_storage_of_unloaded_account() {
+        let address = H160::from_low_u64_be(0x4200);
+        let other = H160::from_low_u64_be(0x4201);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            other,
+            crate::AccountInfo {
+                nonce: 1,
+                ..Default::default()
+            },
+        );
+        db.insert_account_storage(other, U256::from(1), U256::from(7))
+            .unwrap();
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.stateful_precompile(
+            address,
+            move |host: &mut dyn PrecompileHost,
+                  _input: &[u8],
+                  _gas_limit: u64,
+                  _context: &CallContext,
+                  _is_static: bool|
+                  -> PrecompileResult {
+                let (value, is_cold) = host.sload(other, U256::from(1)).unwrap();
+                assert!(is_cold);
+                host.sstore(other, U256::from(2), value).unwrap();
+                Ok(PrecompileOutput::without_logs(100, Vec::new()))
+            },
+        );
+        evm.env.tx.transact_to = TransactTo::Call(address);
+
+        let (result, state) = evm.transact().unwrap();
+        assert!(result.is_success());
+        assert_eq!(
+            state[&other].storage[&U256::from(2)].present_value(),
+            U256::from(7)
+        );
+    }
+
+    #[test]
+    fn custom_precompiles_replace_fork_ones() {
+        fn echo(input: &[u8], _gas_limit: u64) -> PrecompileResult {
+            Ok(PrecompileOutput::without_logs(15, input.to_vec()))
+        }
+        let address = H160::from_low_u64_be(0x0100);
+        let mut precompiles = Precompiles::default();
+        precompiles.insert(address, Precompile::Custom(echo));
+
+        let mut evm = crate::new();
+        evm.database(InMemoryDB::default());
+        evm.precompiles(precompiles);
+        evm.env.tx.transact_to = TransactTo::Call(address);
+        evm.env.tx.data = bytes::Bytes::from_static(&[1, 2, 3]);
+
+        match evm.transact().unwrap().0 {
+            ExecutionResult::Success {
+                output: TransactOut::Call(output),
+                ..
+            } => assert_eq!(output.as_ref(), &[1, 2, 3]),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
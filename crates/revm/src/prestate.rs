@@ -0,0 +1,276 @@
+//! Account snapshots of the transaction, same as geth `prestateTracer`.
+//!
+//! [crate::EVM::transact] does not write to database, so state before the transaction is read
+//! from the database and state after it from returned [State]:
+//!
+//! ```ignore
+//! let (result, state) = evm.transact()?;
+//! let diff = revm::state_diff(evm.db().unwrap(), &state)?;
+//! ```
+use crate::{journaled_state::State, AccountInfo, Database, KECCAK_EMPTY};
+use alloc::collections::BTreeMap;
+use bytes::Bytes;
+use primitive_types::{H160, U256};
+
+#[cfg(feature = "with-serde")]
+use crate::models::serde_hex_bytes_opt;
+
+/// Snapshot of the account. Empty fields are omitted same as in geth.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountSnapshot {
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub balance: Option<U256>,
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub nonce: Option<u64>,
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "serde_hex_bytes_opt"
+        )
+    )]
+    pub code: Option<Bytes>,
+    #[cfg_attr(
+        feature = "with-serde",
+        serde(
+            default,
+            skip_serializing_if = "BTreeMap::is_empty",
+            with = "serde_storage"
+        )
+    )]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// Accounts changed by the transaction, geth `prestateTracer` with `diffMode`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateDiff {
+    /// Changed accounts that existed before the transaction with all their fields and original
+    /// values of changed storage slots.
+    pub pre: BTreeMap<H160, AccountSnapshot>,
+    /// Changed fields and storage slots of accounts that exist after the transaction.
+    /// Slots set to zero are omitted.
+    pub post: BTreeMap<H160, AccountSnapshot>,
+}
+
+/// State of all accounts touched by the transaction, before the transaction was executed.
+///
+/// `db` needs to be in the same state as it was when the transaction was executed.
+pub fn prestate<DB: Database>(
+    db: &mut DB,
+    state: &State,
+) -> Result<BTreeMap<H160, AccountSnapshot>, DB::Error> {
+    let mut prestate = BTreeMap::new();
+    for (address, account) in state {
+        let info = db.basic(*address)?.unwrap_or_default();
+        let mut pre = account_snapshot(db, &info)?;
+        pre.balance = Some(info.balance);
+        pre.storage = account
+            .storage
+            .iter()
+            .map(|(index, slot)| (*index, slot.original_value()))
+            .collect();
+        prestate.insert(*address, pre);
+    }
+    Ok(prestate)
+}
+
+/// Changes done by the transaction, see [StateDiff].
+///
+/// `db` needs to be in the same state as it was when the transaction was executed.
+pub fn state_diff<DB: Database>(db: &mut DB, state: &State) -> Result<StateDiff, DB::Error> {
+    let mut diff = StateDiff::default();
+    for (address, account) in state {
+        let info = db.basic(*address)?;
+        let existed = info.as_ref().map(AccountInfo::exists).unwrap_or_default();
+        let info = info.unwrap_or_default();
+        let deleted = account.is_destroyed || (account.is_touched && account.is_empty());
+
+        let changed_storage = account.storage.iter().filter(|(_, slot)| slot.is_changed());
+        let mut pre = account_snapshot(db, &info)?;
+        pre.balance = Some(info.balance);
+        pre.storage = changed_storage
+            .clone()
+            .map(|(index, slot)| (*index, slot.original_value()))
+            .collect();
+
+        let mut post = AccountSnapshot {
+            storage: changed_storage
+                .filter(|(_, slot)| !slot.present_value().is_zero())
+                .map(|(index, slot)| (*index, slot.present_value()))
+                .collect(),
+            ..Default::default()
+        };
+        if account.info.balance != info.balance {
+            post.balance = Some(account.info.balance);
+        }
+        if account.info.nonce != info.nonce {
+            post.nonce = Some(account.info.nonce);
+        }
+        if account.info.code_hash != info.code_hash {
+            post.code = account_snapshot(db, &account.info)?.code;
+        }
+
+        let modified = deleted
+            || post.balance.is_some()
+            || post.nonce.is_some()
+            || account.info.code_hash != info.code_hash
+            || !pre.storage.is_empty();
+        if !modified {
+            continue;
+        }
+        if existed {
+            diff.pre.insert(*address, pre);
+        }
+        if !deleted {
+            diff.post.insert(*address, post);
+        }
+    }
+    Ok(diff)
+}
+
+/// Account snapshot with nonce and code, empty fields are omitted.
+fn account_snapshot<DB: Database>(
+    db: &mut DB,
+    info: &AccountInfo,
+) -> Result<AccountSnapshot, DB::Error> {
+    let code = if info.code_hash == KECCAK_EMPTY || info.code_hash.is_zero() {
+        None
+    } else {
+        let code = match &info.code {
+            Some(code) => code.clone(),
+            None => db.code_by_hash(info.code_hash)?,
+        };
+        Some(code.bytes().slice(..code.len()))
+    };
+    Ok(AccountSnapshot {
+        nonce: (info.nonce != 0).then_some(info.nonce),
+        code,
+        ..Default::default()
+    })
+}
+
+/// Serde functions to serde storage as map of 32 byte hex words.
+#[cfg(feature = "with-serde")]
+mod serde_storage {
+    use alloc::{collections::BTreeMap, format};
+    use primitive_types::{H256, U256};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        storage: &BTreeMap<U256, U256>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        s.collect_map(
+            storage
+                .iter()
+                .map(|(index, value)| (format!("{:#066x}", index), format!("{:#066x}", value))),
+        )
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<BTreeMap<U256, U256>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let storage = BTreeMap::<H256, H256>::deserialize(d)?;
+        Ok(storage
+            .into_iter()
+            .map(|(index, value)| {
+                (
+                    U256::from_big_endian(index.as_bytes()),
+                    U256::from_big_endian(value.as_bytes()),
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{opcode, test_utils::bytecode, InMemoryDB, TransactTo};
+
+    #[test]
+    fn diff_of_value_transfer_and_sstore() {
+        let caller = H160::from_low_u64_be(0x1000);
+        let contract = H160::from_low_u64_be(0xaa);
+        #[rustfmt::skip]
+        let code = [
+            // SSTORE(1, 0)
+            opcode::PUSH1, 0, opcode::PUSH1, 1, opcode::SSTORE,
+            // SSTORE(2, 3)
+            opcode::PUSH1, 3, opcode::PUSH1, 2, opcode::SSTORE,
+            opcode::STOP,
+        ];
+        let bytecode = bytecode(&code);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(10u64.pow(18))));
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(U256::zero(), 1, bytecode.clone()),
+        );
+        db.insert_account_storage(contract, U256::one(), U256::from(5))
+            .unwrap();
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.caller = caller;
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        evm.env.tx.value = U256::from(7);
+        evm.env.tx.gas_price = U256::one();
+        evm.env.tx.gas_limit = 100_000;
+
+        let (result, state) = evm.transact().unwrap();
+        let db = evm.db().unwrap();
+        let diff = state_diff(db, &state).unwrap();
+
+        let caller_balance = U256::from(10u64.pow(18)) - 7 - result.gas_used();
+        assert_eq!(
+            diff.pre[&caller],
+            AccountSnapshot {
+                balance: Some(U256::from(10u64.pow(18))),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff.post[&caller],
+            AccountSnapshot {
+                balance: Some(caller_balance),
+                nonce: Some(1),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff.pre[&contract],
+            AccountSnapshot {
+                balance: Some(U256::zero()),
+                nonce: Some(1),
+                code: Some(bytecode.bytes().clone()),
+                storage: BTreeMap::from([
+                    (U256::one(), U256::from(5)),
+                    (U256::from(2), U256::zero())
+                ]),
+            }
+        );
+        assert_eq!(
+            diff.post[&contract],
+            AccountSnapshot {
+                balance: Some(U256::from(7)),
+                storage: BTreeMap::from([(U256::from(2), U256::from(3))]),
+                ..Default::default()
+            }
+        );
+
+        let prestate = prestate(db, &state).unwrap();
+        assert_eq!(prestate[&contract], diff.pre[&contract]);
+        assert_eq!(prestate[&caller].nonce, None);
+    }
+}
@@ -0,0 +1,213 @@
+//! Decoding of the revert data returned by `REVERT`.
+use alloc::{format, string::String, sync::Arc};
+use core::fmt;
+use hashbrown::HashMap as Map;
+use primitive_types::U256;
+
+/// Selector of the solidity `Error(string)`.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the solidity `Panic(uint256)`.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decoded revert data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RevertReason {
+    /// `Error(string)` from `require` or `revert` with message.
+    Error(String),
+    /// `Panic(uint256)` code from failed assert, overflow and other checks.
+    Panic(U256),
+    /// Custom error decoded by [RevertDecoder::custom_error] hook.
+    Custom(String),
+}
+
+impl RevertReason {
+    /// Decode `Error(string)` and `Panic(uint256)` revert data.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let (selector, args) = split_selector(data)?;
+        match selector {
+            ERROR_SELECTOR => {
+                let offset = abi_usize(args, 0)?;
+                let len = abi_usize(args, offset)?;
+                let start = offset.checked_add(32)?;
+                let reason = args.get(start..start.checked_add(len)?)?;
+                core::str::from_utf8(reason)
+                    .ok()
+                    .map(|reason| Self::Error(reason.into()))
+            }
+            PANIC_SELECTOR => Some(Self::Panic(U256::from_big_endian(args.get(..32)?))),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RevertReason {
+    /// Panic codes are described same as in geth.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(reason) | Self::Custom(reason) => f.write_str(reason),
+            Self::Panic(code) => {
+                let reason = match code.low_u64() {
+                    _ if *code > U256::from(u64::MAX) => None,
+                    0x00 => Some("generic panic"),
+                    0x01 => Some("assert(false)"),
+                    0x11 => Some("arithmetic underflow or overflow"),
+                    0x12 => Some("division or modulo by zero"),
+                    0x21 => Some("enum overflow"),
+                    0x22 => Some("invalid encoded storage byte array accessed"),
+                    0x31 => Some("out-of-bounds array access; popping on an empty array"),
+                    0x32 => Some("out-of-bounds access of an array or bytesN"),
+                    0x41 => Some("out of memory"),
+                    0x51 => Some("uninitialized function"),
+                    _ => None,
+                };
+                match reason {
+                    Some(reason) => f.write_str(reason),
+                    None => write!(f, "unknown panic code: {:#x}", code),
+                }
+            }
+        }
+    }
+}
+
+/// Decoder of custom error arguments, it gets revert data without the selector.
+pub type CustomErrorDecoder = dyn Fn(&[u8]) -> Option<String> + Send + Sync;
+
+/// Revert data decoder that can be extended with custom errors.
+#[derive(Clone, Default)]
+pub struct RevertDecoder {
+    custom: Map<[u8; 4], Arc<CustomErrorDecoder>>,
+}
+
+impl fmt::Debug for RevertDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RevertDecoder")
+            .field("custom", &self.custom.keys())
+            .finish()
+    }
+}
+
+impl RevertDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register decoder for custom error with given selector.
+    /// Custom decoders take precedence over `Error(string)` and `Panic(uint256)`.
+    pub fn custom_error<F>(&mut self, selector: [u8; 4], decoder: F)
+    where
+        F: Fn(&[u8]) -> Option<String> + Send + Sync + 'static,
+    {
+        self.custom.insert(selector, Arc::new(decoder));
+    }
+
+    /// Decode revert data. Custom errors without decoded reason are shown by their selector.
+    pub fn decode(&self, data: &[u8]) -> Option<RevertReason> {
+        let (selector, args) = split_selector(data)?;
+        match self.custom.get(&selector) {
+            Some(decoder) => {
+                Some(RevertReason::Custom(decoder(args).unwrap_or_else(|| {
+                    format!("custom error 0x{}", hex_selector(&selector))
+                })))
+            }
+            None => RevertReason::decode(data),
+        }
+    }
+}
+
+fn split_selector(data: &[u8]) -> Option<([u8; 4], &[u8])> {
+    let selector = data.get(..4)?.try_into().ok()?;
+    Some((selector, &data[4..]))
+}
+
+fn hex_selector(selector: &[u8; 4]) -> String {
+    selector
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn abi_usize(data: &[u8], offset: usize) -> Option<usize> {
+    let word = data.get(offset..offset.checked_add(32)?)?;
+    let value = U256::from_big_endian(word);
+    (value <= U256::from(usize::MAX)).then(|| value.as_usize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+    use bytes::Bytes;
+
+    fn word(value: u64) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        U256::from(value).to_big_endian(&mut word);
+        word
+    }
+
+    #[test]
+    fn decode_error_and_panic() {
+        let mut error = ERROR_SELECTOR.to_vec();
+        error.extend_from_slice(&word(32));
+        error.extend_from_slice(&word(5));
+        let mut reason = [0u8; 32];
+        reason[..5].copy_from_slice(b"error");
+        error.extend_from_slice(&reason);
+        assert_eq!(
+            RevertReason::decode(&error),
+            Some(RevertReason::Error("error".into()))
+        );
+        assert_eq!(RevertReason::decode(&error[..40]), None);
+
+        let panic = [&PANIC_SELECTOR[..], &word(0x11)].concat();
+        let reason = RevertReason::decode(&panic).unwrap();
+        assert_eq!(reason, RevertReason::Panic(U256::from(0x11)));
+        assert_eq!(reason.to_string(), "arithmetic underflow or overflow");
+    }
+
+    #[test]
+    fn decode_custom_error() {
+        let selector = [1, 2, 3, 4];
+        let mut decoder = RevertDecoder::new();
+        decoder.custom_error(selector, |args: &[u8]| {
+            Some(format!(
+                "InsufficientBalance({})",
+                U256::from_big_endian(args)
+            ))
+        });
+
+        let data: Vec<u8> = [&selector[..], &word(7)].concat();
+        assert_eq!(
+            decoder.decode(&data),
+            Some(RevertReason::Custom("InsufficientBalance(7)".into()))
+        );
+        assert_eq!(decoder.decode(&[5, 6, 7, 8]), None);
+    }
+
+    #[test]
+    fn revert_reason_of_execution_result() {
+        let selector = [1, 2, 3, 4];
+        let mut decoder = RevertDecoder::new();
+        decoder.custom_error(selector, |_: &[u8]| Some("Unauthorized()".into()));
+
+        let result = crate::ExecutionResult::Revert {
+            gas_used: 21_000,
+            output: Bytes::copy_from_slice(&selector),
+        };
+        assert_eq!(
+            result.revert_reason(&decoder),
+            Some(RevertReason::Custom("Unauthorized()".into()))
+        );
+        assert_eq!(result.revert_reason(&RevertDecoder::default()), None);
+
+        let panic = [&PANIC_SELECTOR[..], &word(1)].concat();
+        let result = crate::ExecutionResult::Revert {
+            gas_used: 21_000,
+            output: panic.into(),
+        };
+        assert_eq!(
+            result.revert_reason(&decoder),
+            Some(RevertReason::Panic(U256::one()))
+        );
+    }
+}
@@ -24,8 +24,10 @@ pub enum SpecId {
     LONDON = 12,          // London	                12965000
     ARROW_GLACIER = 13,   // Arrow Glacier	        13773000
     GRAY_GLACIER = 14,    // Gray Glacier	        15050000
-    MERGE = 15,           // Paris/Merge	        TBD (Depends on difficulty)
-    LATEST = 16,
+    MERGE = 15,           // Paris/Merge	        15537394 (TTD: 58750000000000000000000)
+    SHANGHAI = 16,        // Shanghai	            17034870 (Timestamp: 1681338455)
+    CANCUN = 17,          // Cancun	                19426587 (Timestamp: 1710338135)
+    LATEST = 18,
 }
 
 impl SpecId {
@@ -36,7 +38,11 @@ impl SpecId {
             }
             BYZANTIUM | CONSTANTINOPLE | PETERSBURG => PrecompileId::BYZANTIUM,
             ISTANBUL | MUIR_GLACIER => PrecompileId::ISTANBUL,
-            BERLIN | LONDON | ARROW_GLACIER | GRAY_GLACIER | MERGE | LATEST => PrecompileId::BERLIN,
+            BERLIN | LONDON | ARROW_GLACIER | GRAY_GLACIER | MERGE | SHANGHAI => {
+                PrecompileId::BERLIN
+            }
+            CANCUN => PrecompileId::CANCUN,
+            LATEST => PrecompileId::LATEST,
         }
     }
 
@@ -62,6 +68,8 @@ impl From<&str> for SpecId {
             "Berlin" => SpecId::BERLIN,
             "London" => SpecId::LONDON,
             "Merge" => SpecId::MERGE,
+            "Shanghai" => SpecId::SHANGHAI,
+            "Cancun" => SpecId::CANCUN,
             _ => SpecId::LATEST,
         }
     }
@@ -143,11 +151,14 @@ pub(crate) mod spec_impl {
     // ARROW_GLACIER no EVM spec change
     // GRAT_GLACIER no EVM spec change
     spec!(MERGE);
+    spec!(SHANGHAI);
+    spec!(CANCUN);
     spec!(LATEST);
 }
 
 pub use spec_impl::BERLIN::SpecImpl as BerlinSpec;
 pub use spec_impl::BYZANTIUM::SpecImpl as ByzantiumSpec;
+pub use spec_impl::CANCUN::SpecImpl as CancunSpec;
 pub use spec_impl::FRONTIER::SpecImpl as FrontierSpec;
 pub use spec_impl::HOMESTEAD::SpecImpl as HomesteadSpec;
 pub use spec_impl::ISTANBUL::SpecImpl as IstanbulSpec;
@@ -155,5 +166,6 @@ pub use spec_impl::LATEST::SpecImpl as LatestSpec;
 pub use spec_impl::LONDON::SpecImpl as LondonSpec;
 pub use spec_impl::MERGE::SpecImpl as MergeSpec;
 pub use spec_impl::PETERSBURG::SpecImpl as PetersburgSpec;
+pub use spec_impl::SHANGHAI::SpecImpl as ShanghaiSpec;
 pub use spec_impl::SPURIOUS_DRAGON::SpecImpl as SpuriousDragonSpec;
 pub use spec_impl::TANGERINE::SpecImpl as TangerineSpec;
@@ -0,0 +1,24 @@
+//! Fixtures shared by unit tests.
+use crate::{AccountInfo, Bytecode, InMemoryDB};
+use bytes::Bytes;
+use primitive_types::H160;
+
+/// Unanalysed bytecode of the given opcodes.
+pub(crate) fn bytecode(code: &[u8]) -> Bytecode {
+    Bytecode::new_raw(Bytes::copy_from_slice(code))
+}
+
+/// Database with given contracts, they have zero balance and nonce.
+pub(crate) fn db_with_code(contracts: &[(H160, &[u8])]) -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    for (address, code) in contracts {
+        db.insert_account_info(
+            *address,
+            AccountInfo {
+                code: Some(bytecode(code)),
+                ..Default::default()
+            },
+        );
+    }
+    db
+}
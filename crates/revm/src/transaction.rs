@@ -0,0 +1,459 @@
+use crate::{
+    alloc::{vec, vec::Vec},
+    SpecId, TransactTo, TxEnv,
+};
+use bytes::Bytes;
+use primitive_types::{H160, H256, U256};
+use rlp::{DecoderError, Rlp, RlpStream};
+use sha3::{Digest, Keccak256};
+
+/// EIP-2718 transaction type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TxType {
+    /// Legacy transaction, with or without EIP-155 replay protection.
+    Legacy = 0,
+    /// EIP-2930: Optional access lists
+    Eip2930 = 1,
+    /// EIP-1559: Fee market change for ETH 1.0 chain
+    Eip1559 = 2,
+}
+
+impl TxType {
+    /// Returns true if transactions of this type are valid in given spec.
+    pub fn is_enabled(self, spec_id: SpecId) -> bool {
+        match self {
+            Self::Legacy => true,
+            Self::Eip2930 => SpecId::enabled(spec_id, SpecId::BERLIN),
+            Self::Eip1559 => SpecId::enabled(spec_id, SpecId::LONDON),
+        }
+    }
+}
+
+/// Half of the secp256k1 curve order, EIP-2 rejects signatures with `s` above it.
+const SECP256K1N_HALF: U256 = U256([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+/// Errors that can happen while decoding signed transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionDecodeError {
+    /// Transaction is not valid RLP or it has wrong number of fields.
+    Rlp(DecoderError),
+    /// Typed transaction with unknown EIP-2718 type.
+    UnsupportedTxType(u8),
+    /// Signature values are not valid or sender could not be recovered.
+    InvalidSignature,
+}
+
+impl From<DecoderError> for TransactionDecodeError {
+    fn from(err: DecoderError) -> Self {
+        Self::Rlp(err)
+    }
+}
+
+/// Signed transaction decoded from its RLP envelope.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignedTransaction {
+    pub tx_type: TxType,
+    /// Chain id. None for legacy transactions without EIP-155 replay protection.
+    pub chain_id: Option<u64>,
+    pub nonce: u64,
+    /// Gas price for legacy and EIP-2930 transactions, max fee per gas for EIP-1559.
+    pub gas_price: U256,
+    /// Max priority fee per gas, only present in EIP-1559 transactions.
+    pub gas_priority_fee: Option<U256>,
+    pub gas_limit: u64,
+    pub transact_to: TransactTo,
+    pub value: U256,
+    #[cfg_attr(feature = "with-serde", serde(with = "crate::models::serde_hex_bytes"))]
+    pub data: Bytes,
+    pub access_list: Vec<(H160, Vec<U256>)>,
+    /// Recovery id of signature, `0` or `1`.
+    pub odd_y_parity: bool,
+    pub r: U256,
+    pub s: U256,
+    /// Hash of the payload that was signed by the sender.
+    pub signature_hash: H256,
+}
+
+impl SignedTransaction {
+    /// Decode legacy RLP list or EIP-2718 typed transaction envelope (`type || rlp(payload)`).
+    /// Bytes after the RLP list are rejected.
+    pub fn decode(bytes: &[u8]) -> Result<Self, TransactionDecodeError> {
+        let first = *bytes.first().ok_or(DecoderError::RlpIsTooShort)?;
+        // Legacy transaction is RLP list and its first byte is always above 0xc0.
+        if first >= 0xc0 {
+            return Self::decode_legacy(&rlp_list(bytes)?);
+        }
+        let rlp = rlp_list(&bytes[1..])?;
+        match first {
+            0x01 => Self::decode_typed(TxType::Eip2930, &rlp),
+            0x02 => Self::decode_typed(TxType::Eip1559, &rlp),
+            tx_type => Err(TransactionDecodeError::UnsupportedTxType(tx_type)),
+        }
+    }
+
+    fn decode_legacy(rlp: &Rlp) -> Result<Self, TransactionDecodeError> {
+        if rlp.item_count()? != 9 {
+            return Err(DecoderError::RlpIncorrectListLen.into());
+        }
+        let v: u64 = rlp.val_at(6)?;
+        // EIP-155: Simple replay attack protection
+        let (chain_id, odd_y_parity) = match v {
+            27 | 28 => (None, v == 28),
+            v if v >= 35 => (Some((v - 35) / 2), (v - 35) % 2 == 1),
+            _ => return Err(TransactionDecodeError::InvalidSignature),
+        };
+
+        let mut stream = RlpStream::new_list(if chain_id.is_some() { 9 } else { 6 });
+        for i in 0..6 {
+            stream.append_raw(rlp.at(i)?.as_raw(), 1);
+        }
+        if let Some(chain_id) = chain_id {
+            stream.append(&chain_id);
+            stream.append_empty_data();
+            stream.append_empty_data();
+        }
+
+        Ok(Self {
+            tx_type: TxType::Legacy,
+            chain_id,
+            nonce: rlp.val_at(0)?,
+            gas_price: rlp.val_at(1)?,
+            gas_priority_fee: None,
+            gas_limit: rlp.val_at(2)?,
+            transact_to: decode_to(&rlp.at(3)?)?,
+            value: rlp.val_at(4)?,
+            data: Bytes::copy_from_slice(rlp.at(5)?.data()?),
+            access_list: Vec::new(),
+            odd_y_parity,
+            r: rlp.val_at(7)?,
+            s: rlp.val_at(8)?,
+            signature_hash: keccak256(&stream.out()),
+        })
+    }
+
+    fn decode_typed(tx_type: TxType, rlp: &Rlp) -> Result<Self, TransactionDecodeError> {
+        // EIP-1559 has additional field for priority fee.
+        let offset = if tx_type == TxType::Eip1559 { 1 } else { 0 };
+        let fields = 8 + offset;
+        if rlp.item_count()? != fields + 3 {
+            return Err(DecoderError::RlpIncorrectListLen.into());
+        }
+
+        // Signed payload is `type || rlp(fields without signature)`
+        let mut stream = RlpStream::new_list(fields);
+        for i in 0..fields {
+            stream.append_raw(rlp.at(i)?.as_raw(), 1);
+        }
+        let mut payload = vec![tx_type as u8];
+        payload.extend_from_slice(&stream.out());
+
+        let odd_y_parity = match rlp.val_at::<u8>(fields)? {
+            0 => false,
+            1 => true,
+            _ => return Err(TransactionDecodeError::InvalidSignature),
+        };
+
+        Ok(Self {
+            tx_type,
+            chain_id: Some(rlp.val_at(0)?),
+            nonce: rlp.val_at(1)?,
+            gas_price: rlp.val_at(2 + offset)?,
+            gas_priority_fee: if offset == 1 {
+                Some(rlp.val_at(2)?)
+            } else {
+                None
+            },
+            gas_limit: rlp.val_at(3 + offset)?,
+            transact_to: decode_to(&rlp.at(4 + offset)?)?,
+            value: rlp.val_at(5 + offset)?,
+            data: Bytes::copy_from_slice(rlp.at(6 + offset)?.data()?),
+            access_list: decode_access_list(&rlp.at(7 + offset)?)?,
+            odd_y_parity,
+            r: rlp.val_at(fields + 1)?,
+            s: rlp.val_at(fields + 2)?,
+            signature_hash: keccak256(&payload),
+        })
+    }
+
+    /// Recover address of the transaction sender from signature.
+    ///
+    /// EIP-2: Signatures with `s` above half of the curve order are rejected, same as in all
+    /// transactions since Homestead.
+    pub fn recover_caller(&self) -> Result<H160, TransactionDecodeError> {
+        if self.s > SECP256K1N_HALF {
+            return Err(TransactionDecodeError::InvalidSignature);
+        }
+        let mut sig = [0u8; 65];
+        self.r.to_big_endian(&mut sig[0..32]);
+        self.s.to_big_endian(&mut sig[32..64]);
+        sig[64] = self.odd_y_parity as u8;
+        revm_precompiles::ecrecover(&sig, self.signature_hash.as_fixed_bytes())
+            .ok_or(TransactionDecodeError::InvalidSignature)
+    }
+
+    /// Recover sender and create [TxEnv] that can be executed by the EVM.
+    pub fn to_tx_env(&self) -> Result<TxEnv, TransactionDecodeError> {
+        Ok(TxEnv {
+            caller: self.recover_caller()?,
+            gas_limit: self.gas_limit,
+            gas_price: self.gas_price,
+            gas_priority_fee: self.gas_priority_fee,
+            transact_to: self.transact_to.clone(),
+            value: self.value,
+            data: self.data.clone(),
+            chain_id: self.chain_id,
+            nonce: Some(self.nonce),
+            access_list: self.access_list.clone(),
+            blob_hashes: Vec::new(),
+        })
+    }
+}
+
+impl TxEnv {
+    /// Decode signed transaction envelope and fill [TxEnv] with recovered sender.
+    pub fn from_signed_rlp(bytes: &[u8]) -> Result<Self, TransactionDecodeError> {
+        SignedTransaction::decode(bytes)?.to_tx_env()
+    }
+}
+
+/// RLP list that spans all bytes.
+fn rlp_list(bytes: &[u8]) -> Result<Rlp<'_>, DecoderError> {
+    let rlp = Rlp::new(bytes);
+    if !rlp.is_list() {
+        return Err(DecoderError::RlpExpectedToBeList);
+    }
+    if rlp.payload_info()?.total() != bytes.len() {
+        return Err(DecoderError::RlpIsTooBig);
+    }
+    Ok(rlp)
+}
+
+fn keccak256(data: &[u8]) -> H256 {
+    H256::from_slice(Keccak256::digest(data).as_slice())
+}
+
+fn decode_to(rlp: &Rlp) -> Result<TransactTo, DecoderError> {
+    if rlp.is_empty() {
+        Ok(TransactTo::create())
+    } else {
+        Ok(TransactTo::Call(rlp.as_val()?))
+    }
+}
+
+fn decode_access_list(rlp: &Rlp) -> Result<Vec<(H160, Vec<U256>)>, DecoderError> {
+    rlp.iter()
+        .map(|item| {
+            if item.item_count()? != 2 {
+                return Err(DecoderError::RlpIncorrectListLen);
+            }
+            let slots = item
+                .list_at::<H256>(1)?
+                .into_iter()
+                .map(|slot| U256::from_big_endian(slot.as_bytes()))
+                .collect();
+            Ok((item.val_at(0)?, slots))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use secp256k1::{Message, Secp256k1, SecretKey};
+
+    /// Sign typed transaction fields with private key 0x4646..46 and return the envelope.
+    fn sign_typed(tx_type: TxType, fields: &[&[u8]]) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(fields.len());
+        for field in fields {
+            stream.append_raw(field, 1);
+        }
+        let mut payload = vec![tx_type as u8];
+        payload.extend_from_slice(&stream.out());
+
+        let secp = Secp256k1::new();
+        let key = SecretKey::from_slice(&[0x46; 32]).unwrap();
+        let message = Message::from_slice(keccak256(&payload).as_bytes()).unwrap();
+        let (recid, sig) = secp
+            .sign_ecdsa_recoverable(&message, &key)
+            .serialize_compact();
+
+        let mut stream = RlpStream::new_list(fields.len() + 3);
+        for field in fields {
+            stream.append_raw(field, 1);
+        }
+        stream.append(&(recid.to_i32() as u8));
+        stream.append(&U256::from_big_endian(&sig[..32]));
+        stream.append(&U256::from_big_endian(&sig[32..]));
+        let mut envelope = vec![tx_type as u8];
+        envelope.extend_from_slice(&stream.out());
+        envelope
+    }
+
+    fn rlp<T: rlp::Encodable>(value: &T) -> Vec<u8> {
+        rlp::encode(value).to_vec()
+    }
+
+    fn access_list_rlp() -> Vec<u8> {
+        let mut stream = RlpStream::new_list(1);
+        stream.begin_list(2);
+        stream.append(&H160::repeat_byte(0x35));
+        stream.begin_list(2);
+        stream.append(&H256::from_low_u64_be(1));
+        stream.append(&H256::from_low_u64_be(2));
+        stream.out().to_vec()
+    }
+
+    fn legacy_bytes() -> Vec<u8> {
+        // Example from EIP-155, signed with private key 0x4646..46.
+        hex::decode("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83").unwrap()
+    }
+
+    #[test]
+    fn decode_eip155_legacy_transaction() {
+        let tx = SignedTransaction::decode(&legacy_bytes()).unwrap();
+
+        assert_eq!(tx.tx_type, TxType::Legacy);
+        assert_eq!(tx.chain_id, Some(1));
+        assert_eq!(tx.nonce, 9);
+        assert_eq!(tx.gas_price, U256::from(20_000_000_000u64));
+        assert_eq!(tx.gas_limit, 21000);
+        assert_eq!(
+            tx.signature_hash,
+            H256::from_str("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53")
+                .unwrap()
+        );
+
+        let tx_env = tx.to_tx_env().unwrap();
+        assert_eq!(
+            tx_env.caller,
+            H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap()
+        );
+        assert_eq!(
+            tx_env.transact_to,
+            TransactTo::Call(H160::from_str("3535353535353535353535353535353535353535").unwrap())
+        );
+        assert_eq!(tx_env.value, U256::from(1_000_000_000_000_000_000u64));
+    }
+
+    #[test]
+    fn decode_unsupported_tx_type() {
+        assert_eq!(
+            SignedTransaction::decode(&[0x7f, 0xc0]),
+            Err(TransactionDecodeError::UnsupportedTxType(0x7f))
+        );
+    }
+
+    #[test]
+    fn decode_eip2930_transaction() {
+        let bytes = sign_typed(
+            TxType::Eip2930,
+            &[
+                &rlp(&5u64),
+                &rlp(&3u64),
+                &rlp(&U256::from(1_000_000_000u64)),
+                &rlp(&30_000u64),
+                &rlp(&H160::repeat_byte(0x35)),
+                &rlp(&U256::from(10)),
+                &rlp(&vec![0xaau8, 0xbb]),
+                &access_list_rlp(),
+            ],
+        );
+        let tx = SignedTransaction::decode(&bytes).unwrap();
+
+        assert_eq!(tx.tx_type, TxType::Eip2930);
+        assert_eq!(tx.chain_id, Some(5));
+        assert_eq!(tx.nonce, 3);
+        assert_eq!(tx.gas_price, U256::from(1_000_000_000u64));
+        assert_eq!(tx.gas_priority_fee, None);
+        assert_eq!(tx.data, Bytes::from_static(&[0xaa, 0xbb]));
+        assert_eq!(
+            tx.access_list,
+            vec![(H160::repeat_byte(0x35), vec![U256::from(1), U256::from(2)])]
+        );
+
+        let tx_env = tx.to_tx_env().unwrap();
+        assert_eq!(
+            tx_env.caller,
+            H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap()
+        );
+        assert_eq!(
+            tx_env.transact_to,
+            TransactTo::Call(H160::repeat_byte(0x35))
+        );
+        assert_eq!(tx_env.value, U256::from(10));
+    }
+
+    #[test]
+    fn decode_eip1559_transaction() {
+        let bytes = sign_typed(
+            TxType::Eip1559,
+            &[
+                &rlp(&1u64),
+                &rlp(&0u64),
+                &rlp(&U256::from(2_000_000_000u64)),
+                &rlp(&U256::from(50_000_000_000u64)),
+                &rlp(&100_000u64),
+                &rlp(&""),
+                &rlp(&U256::zero()),
+                &rlp(&vec![0x60u8, 0x00]),
+                // Empty access list.
+                &[0xc0],
+            ],
+        );
+        let tx = SignedTransaction::decode(&bytes).unwrap();
+
+        assert_eq!(tx.tx_type, TxType::Eip1559);
+        assert_eq!(tx.chain_id, Some(1));
+        assert_eq!(tx.gas_priority_fee, Some(U256::from(2_000_000_000u64)));
+        assert_eq!(tx.gas_price, U256::from(50_000_000_000u64));
+        assert_eq!(tx.gas_limit, 100_000);
+        assert!(tx.access_list.is_empty());
+
+        let tx_env = tx.to_tx_env().unwrap();
+        assert_eq!(
+            tx_env.caller,
+            H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap()
+        );
+        assert_eq!(tx_env.transact_to, TransactTo::create());
+    }
+
+    #[test]
+    fn reject_high_s_signature() {
+        let mut tx = SignedTransaction::decode(&legacy_bytes()).unwrap();
+        // Flip signature to its high-s form that is valid for ecrecover but rejected by EIP-2.
+        let n = SECP256K1N_HALF * 2 + 1;
+        tx.s = n - tx.s;
+        tx.odd_y_parity = !tx.odd_y_parity;
+        assert_eq!(
+            tx.recover_caller(),
+            Err(TransactionDecodeError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn reject_trailing_bytes() {
+        let mut bytes = legacy_bytes();
+        bytes.push(0x00);
+        assert_eq!(
+            SignedTransaction::decode(&bytes),
+            Err(TransactionDecodeError::Rlp(DecoderError::RlpIsTooBig))
+        );
+    }
+
+    #[test]
+    fn tx_type_activation() {
+        assert!(TxType::Legacy.is_enabled(SpecId::FRONTIER));
+        assert!(!TxType::Eip2930.is_enabled(SpecId::ISTANBUL));
+        assert!(TxType::Eip2930.is_enabled(SpecId::BERLIN));
+        assert!(!TxType::Eip1559.is_enabled(SpecId::BERLIN));
+        assert!(TxType::Eip1559.is_enabled(SpecId::LONDON));
+    }
+}
@@ -0,0 +1,53 @@
+use primitive_types::{H160, U256};
+use revm::{
+    apply_block_rewards, apply_dao_fork, apply_withdrawals, block_reward, AccountInfo, BlockEnv,
+    Database, InMemoryDB, SpecId, Withdrawal, DAO_DRAIN_LIST, DAO_HARDFORK_BENEFICIARY,
+    DAO_HARDFORK_BLOCK, GWEI_TO_WEI,
+};
+
+fn balance(db: &mut InMemoryDB, address: H160) -> U256 {
+    db.basic(address)
+        .unwrap()
+        .map(|info| info.balance)
+        .unwrap_or_default()
+}
+
+#[test]
+fn apply_block_changes() {
+    let miner = H160::from_low_u64_be(1);
+    let ommer = H160::from_low_u64_be(2);
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(DAO_DRAIN_LIST[0], AccountInfo::from_balance(U256::from(7)));
+    let block = BlockEnv {
+        number: U256::from(DAO_HARDFORK_BLOCK),
+        coinbase: miner,
+        ..Default::default()
+    };
+
+    apply_dao_fork(&mut db, SpecId::DAO_FORK, &block).unwrap();
+    apply_block_rewards(
+        &mut db,
+        SpecId::DAO_FORK,
+        &block,
+        &[(ommer, block.number - 1)],
+    )
+    .unwrap();
+    apply_withdrawals(
+        &mut db,
+        &[Withdrawal {
+            address: ommer,
+            amount: 2,
+            ..Default::default()
+        }],
+    )
+    .unwrap();
+
+    let reward = block_reward(SpecId::DAO_FORK);
+    assert_eq!(balance(&mut db, DAO_DRAIN_LIST[0]), U256::zero());
+    assert_eq!(balance(&mut db, DAO_HARDFORK_BENEFICIARY), U256::from(7));
+    assert_eq!(balance(&mut db, miner), reward + reward / 32);
+    assert_eq!(
+        balance(&mut db, ommer),
+        reward * 7 / 8 + U256::from(2 * GWEI_TO_WEI)
+    );
+}
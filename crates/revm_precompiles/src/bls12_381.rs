@@ -0,0 +1,677 @@
+//! EIP-2537: Precompile for BLS12-381 curve operations
+//!
+//! Field elements are encoded as 64 bytes, big endian value padded with 16 zero bytes. G1 point is
+//! encoded as `x || y` (128 bytes) and G2 point as `x.c0 || x.c1 || y.c0 || y.c1` (256 bytes).
+//! Point at infinity is encoded as all zeros.
+use crate::{gas_query, Precompile, PrecompileOutput, PrecompileResult, Return};
+use alloc::{borrow::Cow, vec::Vec};
+use blst::{
+    blst_bendian_from_fp, blst_final_exp, blst_fp, blst_fp12, blst_fp12_is_one, blst_fp12_mul,
+    blst_fp12_one, blst_fp2, blst_fp_from_bendian, blst_map_to_g1, blst_map_to_g2,
+    blst_miller_loop, blst_p1, blst_p1_add_or_double, blst_p1_add_or_double_affine, blst_p1_affine,
+    blst_p1_affine_in_g1, blst_p1_affine_is_inf, blst_p1_affine_on_curve, blst_p1_from_affine,
+    blst_p1_mult, blst_p1_to_affine, blst_p2, blst_p2_add_or_double, blst_p2_add_or_double_affine,
+    blst_p2_affine, blst_p2_affine_in_g2, blst_p2_affine_is_inf, blst_p2_affine_on_curve,
+    blst_p2_from_affine, blst_p2_mult, blst_p2_to_affine, blst_scalar, blst_scalar_from_bendian,
+};
+use core::ptr;
+use primitive_types::H160 as Address;
+
+pub const G1_ADD: (Address, Precompile) = (
+    crate::make_address(0, 0x0b),
+    Precompile::Standard(g1_add_run),
+);
+pub const G1_MSM: (Address, Precompile) = (
+    crate::make_address(0, 0x0c),
+    Precompile::Standard(g1_msm_run),
+);
+pub const G2_ADD: (Address, Precompile) = (
+    crate::make_address(0, 0x0d),
+    Precompile::Standard(g2_add_run),
+);
+pub const G2_MSM: (Address, Precompile) = (
+    crate::make_address(0, 0x0e),
+    Precompile::Standard(g2_msm_run),
+);
+pub const PAIRING: (Address, Precompile) = (
+    crate::make_address(0, 0x0f),
+    Precompile::Standard(pairing_run),
+);
+pub const MAP_FP_TO_G1: (Address, Precompile) = (
+    crate::make_address(0, 0x10),
+    Precompile::Standard(map_fp_to_g1_run),
+);
+pub const MAP_FP2_TO_G2: (Address, Precompile) = (
+    crate::make_address(0, 0x11),
+    Precompile::Standard(map_fp2_to_g2_run),
+);
+
+/// All EIP-2537 precompiles.
+pub fn precompiles() -> Vec<(Address, Precompile)> {
+    vec![
+        G1_ADD,
+        G1_MSM,
+        G2_ADD,
+        G2_MSM,
+        PAIRING,
+        MAP_FP_TO_G1,
+        MAP_FP2_TO_G2,
+    ]
+}
+
+const G1_ADD_GAS: u64 = 375;
+const G1_MUL_GAS: u64 = 12_000;
+const G2_ADD_GAS: u64 = 600;
+const G2_MUL_GAS: u64 = 22_500;
+const PAIRING_BASE_GAS: u64 = 37_700;
+const PAIRING_PER_PAIR_GAS: u64 = 32_600;
+const MAP_FP_TO_G1_GAS: u64 = 5_500;
+const MAP_FP2_TO_G2_GAS: u64 = 23_800;
+
+/// MSM discount multiplier.
+const MSM_MULTIPLIER: u64 = 1000;
+/// G1 MSM discounts for `k` pairs, `k` above table length uses the last value.
+const G1_MSM_DISCOUNT: [u16; 128] = [
+    1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677, 673, 669, 665,
+    661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627, 625, 623, 621, 619, 617, 615,
+    613, 611, 609, 608, 606, 604, 603, 601, 599, 598, 596, 595, 593, 592, 591, 589, 588, 586, 585,
+    584, 582, 581, 580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564, 563,
+    562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549, 548, 547, 547, 546, 545,
+    544, 543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534, 533, 532, 532, 531, 530, 529,
+    528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520, 520, 519,
+];
+/// G2 MSM discounts for `k` pairs, `k` above table length uses the last value.
+const G2_MSM_DISCOUNT: [u16; 128] = [
+    1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717, 711, 704,
+    699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646, 643, 640, 637, 634, 632,
+    629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607, 606, 604, 602, 600, 598, 597, 595, 593,
+    592, 590, 589, 587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571, 570, 569, 568,
+    567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 552, 551, 550, 549,
+    548, 547, 546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537, 537, 536, 535, 535, 534,
+    533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+const FP_LEN: usize = 48;
+const PADDED_FP_LEN: usize = 64;
+const PADDED_FP2_LEN: usize = 2 * PADDED_FP_LEN;
+const G1_LEN: usize = 2 * PADDED_FP_LEN;
+const G2_LEN: usize = 2 * PADDED_FP2_LEN;
+const SCALAR_LEN: usize = 32;
+
+/// Big endian field modulus.
+const MODULUS: [u8; FP_LEN] = [
+    0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+    0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+    0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+fn error(msg: &'static str) -> Return {
+    Return::Other(Cow::Borrowed(msg))
+}
+
+fn check_input_len(input: &[u8], len: usize) -> Result<(), Return> {
+    if input.len() != len {
+        return Err(error("ERR_BLS12_INVALID_INPUT_LENGTH"));
+    }
+    Ok(())
+}
+
+/// Number of `len` sized items in input. Input needs to have at least one item.
+fn items_count(input: &[u8], len: usize) -> Result<usize, Return> {
+    let k = input.len() / len;
+    if k == 0 || k * len != input.len() {
+        return Err(error("ERR_BLS12_INVALID_INPUT_LENGTH"));
+    }
+    Ok(k)
+}
+
+fn msm_cost(k: usize, mul_cost: u64, discount_table: &[u16]) -> u64 {
+    let discount = discount_table[k.min(discount_table.len()) - 1] as u64;
+    (k as u64).saturating_mul(mul_cost).saturating_mul(discount) / MSM_MULTIPLIER
+}
+
+fn read_fp(input: &[u8]) -> Result<blst_fp, Return> {
+    let (padding, value) = input.split_at(PADDED_FP_LEN - FP_LEN);
+    if padding.iter().any(|b| *b != 0) {
+        return Err(error("ERR_BLS12_INVALID_FP_PADDING"));
+    }
+    // both are big endian of the same length so lexicographical order is enough.
+    if value >= &MODULUS[..] {
+        return Err(error("ERR_BLS12_FP_NOT_IN_FIELD"));
+    }
+    let mut fp = blst_fp::default();
+    // SAFETY: value is exactly 48 bytes long.
+    unsafe { blst_fp_from_bendian(&mut fp, value.as_ptr()) };
+    Ok(fp)
+}
+
+fn read_fp2(input: &[u8]) -> Result<blst_fp2, Return> {
+    Ok(blst_fp2 {
+        fp: [
+            read_fp(&input[..PADDED_FP_LEN])?,
+            read_fp(&input[PADDED_FP_LEN..PADDED_FP2_LEN])?,
+        ],
+    })
+}
+
+fn encode_fp(fp: &blst_fp, out: &mut [u8]) {
+    // SAFETY: out is 64 bytes long and value is written into the last 48 bytes.
+    unsafe { blst_bendian_from_fp(out[PADDED_FP_LEN - FP_LEN..].as_mut_ptr(), fp) };
+}
+
+fn read_scalar(input: &[u8]) -> blst_scalar {
+    let mut scalar = blst_scalar::default();
+    // SAFETY: input is exactly 32 bytes long.
+    unsafe { blst_scalar_from_bendian(&mut scalar, input.as_ptr()) };
+    scalar
+}
+
+/// Read G1 point and check that it is on the curve. Subgroup check is needed for all operations
+/// except addition.
+fn read_g1(input: &[u8], subgroup_check: bool) -> Result<blst_p1_affine, Return> {
+    let point = blst_p1_affine {
+        x: read_fp(&input[..PADDED_FP_LEN])?,
+        y: read_fp(&input[PADDED_FP_LEN..G1_LEN])?,
+    };
+    // SAFETY: point is valid reference.
+    unsafe {
+        if !blst_p1_affine_on_curve(&point) {
+            return Err(error("ERR_BLS12_G1_NOT_ON_CURVE"));
+        }
+        if subgroup_check && !blst_p1_affine_in_g1(&point) {
+            return Err(error("ERR_BLS12_G1_NOT_IN_SUBGROUP"));
+        }
+    }
+    Ok(point)
+}
+
+fn read_g2(input: &[u8], subgroup_check: bool) -> Result<blst_p2_affine, Return> {
+    let point = blst_p2_affine {
+        x: read_fp2(&input[..PADDED_FP2_LEN])?,
+        y: read_fp2(&input[PADDED_FP2_LEN..G2_LEN])?,
+    };
+    // SAFETY: point is valid reference.
+    unsafe {
+        if !blst_p2_affine_on_curve(&point) {
+            return Err(error("ERR_BLS12_G2_NOT_ON_CURVE"));
+        }
+        if subgroup_check && !blst_p2_affine_in_g2(&point) {
+            return Err(error("ERR_BLS12_G2_NOT_IN_SUBGROUP"));
+        }
+    }
+    Ok(point)
+}
+
+fn encode_g1(point: &blst_p1) -> Vec<u8> {
+    let mut affine = blst_p1_affine::default();
+    // SAFETY: both are valid references. Infinity is converted to all zeros.
+    unsafe { blst_p1_to_affine(&mut affine, point) };
+    let mut out = vec![0u8; G1_LEN];
+    encode_fp(&affine.x, &mut out[..PADDED_FP_LEN]);
+    encode_fp(&affine.y, &mut out[PADDED_FP_LEN..]);
+    out
+}
+
+fn encode_g2(point: &blst_p2) -> Vec<u8> {
+    let mut affine = blst_p2_affine::default();
+    // SAFETY: both are valid references. Infinity is converted to all zeros.
+    unsafe { blst_p2_to_affine(&mut affine, point) };
+    let mut out = vec![0u8; G2_LEN];
+    for (i, fp) in [
+        affine.x.fp[0],
+        affine.x.fp[1],
+        affine.y.fp[0],
+        affine.y.fp[1],
+    ]
+    .iter()
+    .enumerate()
+    {
+        encode_fp(fp, &mut out[i * PADDED_FP_LEN..(i + 1) * PADDED_FP_LEN]);
+    }
+    out
+}
+
+fn g1_mul(point: &blst_p1_affine, scalar: &blst_scalar) -> blst_p1 {
+    let mut p = blst_p1::default();
+    let mut out = blst_p1::default();
+    // SAFETY: all are valid references and scalar has 256 bits.
+    unsafe {
+        blst_p1_from_affine(&mut p, point);
+        blst_p1_mult(&mut out, &p, scalar.b.as_ptr(), 8 * SCALAR_LEN);
+    }
+    out
+}
+
+fn g2_mul(point: &blst_p2_affine, scalar: &blst_scalar) -> blst_p2 {
+    let mut p = blst_p2::default();
+    let mut out = blst_p2::default();
+    // SAFETY: all are valid references and scalar has 256 bits.
+    unsafe {
+        blst_p2_from_affine(&mut p, point);
+        blst_p2_mult(&mut out, &p, scalar.b.as_ptr(), 8 * SCALAR_LEN);
+    }
+    out
+}
+
+fn g1_add_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let cost = gas_query(G1_ADD_GAS, gas_limit)?;
+    check_input_len(input, 2 * G1_LEN)?;
+    let a = read_g1(&input[..G1_LEN], false)?;
+    let b = read_g1(&input[G1_LEN..], false)?;
+
+    let mut p = blst_p1::default();
+    let mut out = blst_p1::default();
+    // SAFETY: all are valid references.
+    unsafe {
+        blst_p1_from_affine(&mut p, &a);
+        blst_p1_add_or_double_affine(&mut out, &p, &b);
+    }
+    Ok(PrecompileOutput::without_logs(cost, encode_g1(&out)))
+}
+
+fn g1_msm_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let k = items_count(input, G1_LEN + SCALAR_LEN)?;
+    let cost = gas_query(msm_cost(k, G1_MUL_GAS, &G1_MSM_DISCOUNT), gas_limit)?;
+
+    let mut acc = blst_p1::default();
+    for item in input.chunks_exact(G1_LEN + SCALAR_LEN) {
+        let point = read_g1(&item[..G1_LEN], true)?;
+        let term = g1_mul(&point, &read_scalar(&item[G1_LEN..]));
+        let prev = acc;
+        // SAFETY: all are valid references.
+        unsafe { blst_p1_add_or_double(&mut acc, &prev, &term) };
+    }
+    Ok(PrecompileOutput::without_logs(cost, encode_g1(&acc)))
+}
+
+fn g2_add_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let cost = gas_query(G2_ADD_GAS, gas_limit)?;
+    check_input_len(input, 2 * G2_LEN)?;
+    let a = read_g2(&input[..G2_LEN], false)?;
+    let b = read_g2(&input[G2_LEN..], false)?;
+
+    let mut p = blst_p2::default();
+    let mut out = blst_p2::default();
+    // SAFETY: all are valid references.
+    unsafe {
+        blst_p2_from_affine(&mut p, &a);
+        blst_p2_add_or_double_affine(&mut out, &p, &b);
+    }
+    Ok(PrecompileOutput::without_logs(cost, encode_g2(&out)))
+}
+
+fn g2_msm_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let k = items_count(input, G2_LEN + SCALAR_LEN)?;
+    let cost = gas_query(msm_cost(k, G2_MUL_GAS, &G2_MSM_DISCOUNT), gas_limit)?;
+
+    let mut acc = blst_p2::default();
+    for item in input.chunks_exact(G2_LEN + SCALAR_LEN) {
+        let point = read_g2(&item[..G2_LEN], true)?;
+        let term = g2_mul(&point, &read_scalar(&item[G2_LEN..]));
+        let prev = acc;
+        // SAFETY: all are valid references.
+        unsafe { blst_p2_add_or_double(&mut acc, &prev, &term) };
+    }
+    Ok(PrecompileOutput::without_logs(cost, encode_g2(&acc)))
+}
+
+/// Output is 32 bytes, with last byte set to one if product of pairings is equal to one.
+fn pairing_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let k = items_count(input, G1_LEN + G2_LEN)?;
+    let cost = gas_query(
+        PAIRING_PER_PAIR_GAS
+            .saturating_mul(k as u64)
+            .saturating_add(PAIRING_BASE_GAS),
+        gas_limit,
+    )?;
+
+    // SAFETY: blst_fp12_one returns pointer to static value.
+    let mut acc = unsafe { *blst_fp12_one() };
+    for item in input.chunks_exact(G1_LEN + G2_LEN) {
+        let p = read_g1(&item[..G1_LEN], true)?;
+        let q = read_g2(&item[G1_LEN..], true)?;
+        // SAFETY: all are valid references.
+        unsafe {
+            // pairing with infinity is one and it does not change the product.
+            if blst_p1_affine_is_inf(&p) || blst_p2_affine_is_inf(&q) {
+                continue;
+            }
+            let mut ml = blst_fp12::default();
+            blst_miller_loop(&mut ml, &q, &p);
+            let prev = acc;
+            blst_fp12_mul(&mut acc, &prev, &ml);
+        }
+    }
+
+    let mut result = blst_fp12::default();
+    // SAFETY: all are valid references.
+    let is_one = unsafe {
+        blst_final_exp(&mut result, &acc);
+        blst_fp12_is_one(&result)
+    };
+    let mut out = vec![0u8; 32];
+    out[31] = is_one as u8;
+    Ok(PrecompileOutput::without_logs(cost, out))
+}
+
+fn map_fp_to_g1_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let cost = gas_query(MAP_FP_TO_G1_GAS, gas_limit)?;
+    check_input_len(input, PADDED_FP_LEN)?;
+    let fp = read_fp(input)?;
+
+    let mut out = blst_p1::default();
+    // SAFETY: all are valid references, `v` can be null.
+    unsafe { blst_map_to_g1(&mut out, &fp, ptr::null()) };
+    Ok(PrecompileOutput::without_logs(cost, encode_g1(&out)))
+}
+
+fn map_fp2_to_g2_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let cost = gas_query(MAP_FP2_TO_G2_GAS, gas_limit)?;
+    check_input_len(input, PADDED_FP2_LEN)?;
+    let fp2 = read_fp2(input)?;
+
+    let mut out = blst_p2::default();
+    // SAFETY: all are valid references, `v` can be null.
+    unsafe { blst_map_to_g2(&mut out, &fp2, ptr::null()) };
+    Ok(PrecompileOutput::without_logs(cost, encode_g2(&out)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Points and expected results from EIP-2537 test vectors (`assets/eip-2537`).
+    const G1: &str = "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1";
+    const P1: &str = "00000000000000000000000000000000112b98340eee2777cc3c14163dea3ec97977ac3dc5c70da32e6e87578f44912e902ccef9efe28d4a78b8999dfbca942600000000000000000000000000000000186b28d92356c4dfec4b5201ad099dbdede3781f8998ddf929b4cd7756192185ca7b8f4ef7088f813270ac3d48868a21";
+    const G1_PLUS_P1: &str = "000000000000000000000000000000000a40300ce2dec9888b60690e9a41d3004fda4886854573974fab73b046d3147ba5b7a5bde85279ffede1b45b3918d82d0000000000000000000000000000000006d3d887e9f53b9ec4eb6cedf5607226754b07c01ace7834f57f3e7315faefb739e59018e22c492006190fba4a870025";
+    const TWO_G1: &str = "000000000000000000000000000000000572cbea904d67468808c8eb50a9450c9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e00000000000000000000000000000000166a9d8cabc673a322fda673779d8e3822ba3ecb8670e461f73bb9021d5fd76a4c56d9d4cd16bd1bba86881979749d28";
+    const NEG_G1: &str = "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb00000000000000000000000000000000114d1d6855d545a8aa7d76c8cf2e21f267816aef1db507c96655b9d5caac42364e6f38ba0ecb751bad54dcd6b939c2ca";
+    const G2: &str = "00000000000000000000000000000000024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80000000000000000000000000000000013e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e000000000000000000000000000000000ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801000000000000000000000000000000000606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be";
+    const P2: &str = "00000000000000000000000000000000103121a2ceaae586d240843a398967325f8eb5a93e8fea99b62b9f88d8556c80dd726a4b30e84a36eeabaf3592937f2700000000000000000000000000000000086b990f3da2aeac0a36143b7d7c824428215140db1bb859338764cb58458f081d92664f9053b50b3fbd2e4723121b68000000000000000000000000000000000f9e7ba9a86a8f7624aa2b42dcc8772e1af4ae115685e60abc2c9b90242167acef3d0be4050bf935eed7c3b6fc7ba77e000000000000000000000000000000000d22c3652d0dc6f0fc9316e14268477c2049ef772e852108d269d9c38dba1d4802e8dae479818184c08f9a569d878451";
+    const G2_PLUS_P2: &str = "000000000000000000000000000000000b54a8a7b08bd6827ed9a797de216b8c9057b3a9ca93e2f88e7f04f19accc42da90d883632b9ca4dc38d013f71ede4db00000000000000000000000000000000077eba4eecf0bd764dce8ed5f45040dd8f3b3427cb35230509482c14651713282946306247866dfe39a8e33016fcbe520000000000000000000000000000000014e60a76a29ef85cbd69f251b9f29147b67cfe3ed2823d3f9776b3a0efd2731941d47436dc6d2b58d9e65f8438bad073000000000000000000000000000000001586c3c910d95754fef7a732df78e279c3d37431c6a2b77e67a00c7c130a8fcd4d19f159cbeb997a178108fffffcbd20";
+    const FP: &str = "00000000000000000000000000000000156c8a6a2c184569d69a76be144b5cdc5141d2d2ca4fe341f011e25e3969c55ad9e9b9ce2eb833c81a908e5fa4ac5f03";
+    const FP_TO_G1: &str = "00000000000000000000000000000000184bb665c37ff561a89ec2122dd343f20e0f4cbcaec84e3c3052ea81d1834e192c426074b02ed3dca4e7676ce4ce48ba0000000000000000000000000000000004407b8d35af4dacc809927071fc0405218f1401a6d15af775810e4e460064bcc9468beeba82fdc751be70476c888bf3";
+    const FP2: &str = "0000000000000000000000000000000007355d25caf6e7f2f0cb2812ca0e513bd026ed09dda65b177500fa31714e09ea0ded3a078b526bed3307f804d4b93b040000000000000000000000000000000002829ce3c021339ccb5caf3e187f6370e1e2a311dec9b75363117063ab2015603ff52c3d3b98f19c2f65575e99e8b78c";
+    const FP2_TO_G2: &str = "0000000000000000000000000000000000e7f4568a82b4b7dc1f14c6aaa055edf51502319c723c4dc2688c7fe5944c213f510328082396515734b6612c4e7bb700000000000000000000000000000000126b855e9e69b1f691f816e48ac6977664d24d99f8724868a184186469ddfd4617367e94527d4b74fc86413483afb35b000000000000000000000000000000000caead0fd7b6176c01436833c79d305c78be307da5f6af6c133c47311def6ff1e0babf57a0fb5539fce7ee12407b0a42000000000000000000000000000000001498aadcf7ae2b345243e281ae076df6de84455d766ab6fcdaad71fab60abb2e8b980a440043cd305db09d283c895e3d";
+    /// Point `(4, y)` is on the curve but it is not in G1 subgroup.
+    const NOT_IN_SUBGROUP_G1: &str = "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000004000000000000000000000000000000000a989badd40d6212b33cffc3f3763e9bc760f988c9926b26da9dd85e928483446346b8ed00e1de5d5ea93e354abe706c";
+
+    fn scalar(value: u8) -> Vec<u8> {
+        let mut out = vec![0u8; SCALAR_LEN];
+        out[31] = value;
+        out
+    }
+
+    fn input(parts: &[&str]) -> Vec<u8> {
+        hex::decode(parts.concat()).unwrap()
+    }
+
+    /// Run precompile and check its output and gas against test vector.
+    fn check(
+        fun: fn(&[u8], u64) -> PrecompileResult,
+        name: &str,
+        input: &[u8],
+        expected: &str,
+        gas: u64,
+    ) {
+        let out = fun(input, u64::MAX).unwrap_or_else(|e| panic!("{}: {:?}", name, e));
+        assert_eq!(hex::encode(out.output), expected, "{}", name);
+        assert_eq!(out.cost, gas, "{}", name);
+    }
+
+    #[test]
+    fn addresses() {
+        let addresses: Vec<_> = precompiles()
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect();
+        let expected: Vec<_> = (0x0b..=0x11).map(|i| crate::make_address(0, i)).collect();
+        assert_eq!(addresses, expected);
+    }
+
+    #[test]
+    fn g1_add() {
+        let inf = "00".repeat(G1_LEN);
+        check(
+            g1_add_run,
+            "bls_g1add_g1+p1",
+            &input(&[G1, P1]),
+            G1_PLUS_P1,
+            375,
+        );
+        check(
+            g1_add_run,
+            "bls_g1add_p1+g1",
+            &input(&[P1, G1]),
+            G1_PLUS_P1,
+            375,
+        );
+        check(
+            g1_add_run,
+            "bls_g1add_(g1+0=g1)",
+            &input(&[G1, &inf]),
+            G1,
+            375,
+        );
+        check(
+            g1_add_run,
+            "bls_g1add_(g1-g1=0)",
+            &input(&[G1, NEG_G1]),
+            &inf,
+            375,
+        );
+        check(
+            g1_add_run,
+            "bls_g1add_(g+g=2g)",
+            &input(&[G1, G1]),
+            TWO_G1,
+            375,
+        );
+    }
+
+    #[test]
+    fn g1_msm() {
+        let inf = "00".repeat(G1_LEN);
+        let two = hex::encode(scalar(2));
+        let one = hex::encode(scalar(1));
+        let zero = hex::encode(scalar(0));
+        check(
+            g1_msm_run,
+            "bls_g1mul_(g1+g1=2*g1)",
+            &input(&[G1, &two]),
+            TWO_G1,
+            12_000,
+        );
+        check(
+            g1_msm_run,
+            "bls_g1mul_(1*p1=p1)",
+            &input(&[P1, &one]),
+            P1,
+            12_000,
+        );
+        check(
+            g1_msm_run,
+            "bls_g1mul_(0*g1=inf)",
+            &input(&[G1, &zero]),
+            &inf,
+            12_000,
+        );
+        check(
+            g1_msm_run,
+            "bls_g1mul_(x*inf=inf)",
+            &input(&[&inf, &two]),
+            &inf,
+            12_000,
+        );
+        check(
+            g1_msm_run,
+            "bls_g1multiexp_(g1+g1=2*g1)",
+            &input(&[G1, &one, G1, &one]),
+            TWO_G1,
+            22_776,
+        );
+    }
+
+    #[test]
+    fn g2_add() {
+        let inf = "00".repeat(G2_LEN);
+        check(
+            g2_add_run,
+            "bls_g2add_g2+p2",
+            &input(&[G2, P2]),
+            G2_PLUS_P2,
+            600,
+        );
+        check(
+            g2_add_run,
+            "bls_g2add_p2+g2",
+            &input(&[P2, G2]),
+            G2_PLUS_P2,
+            600,
+        );
+        check(
+            g2_add_run,
+            "bls_g2add_(g2+0=g2)",
+            &input(&[G2, &inf]),
+            G2,
+            600,
+        );
+    }
+
+    #[test]
+    fn g2_msm() {
+        let inf = "00".repeat(G2_LEN);
+        let one = hex::encode(scalar(1));
+        let zero = hex::encode(scalar(0));
+        check(
+            g2_msm_run,
+            "bls_g2mul_(1*p2=p2)",
+            &input(&[P2, &one]),
+            P2,
+            22_500,
+        );
+        check(
+            g2_msm_run,
+            "bls_g2mul_(0*g2=inf)",
+            &input(&[G2, &zero]),
+            &inf,
+            22_500,
+        );
+        check(
+            g2_msm_run,
+            "bls_g2multiexp_(0*g2+1*p2=p2)",
+            &input(&[G2, &zero, P2, &one]),
+            P2,
+            45_000,
+        );
+    }
+
+    #[test]
+    fn pairing() {
+        let one = format!("{:0>64}", "1");
+        let zero = "00".repeat(32);
+        let g1_inf = "00".repeat(G1_LEN);
+        let g2_inf = "00".repeat(G2_LEN);
+        check(
+            pairing_run,
+            "bls_pairing_e(G1,0)=e(0,G2)",
+            &input(&[G1, &g2_inf, &g1_inf, G2]),
+            &one,
+            102_900,
+        );
+        check(
+            pairing_run,
+            "bls_pairing_non-degeneracy",
+            &input(&[G1, G2]),
+            &zero,
+            70_300,
+        );
+        check(
+            pairing_run,
+            "bls_pairing_e(G1,G2)=e(-G1,G2)^-1",
+            &input(&[G1, G2, NEG_G1, G2]),
+            &one,
+            102_900,
+        );
+    }
+
+    #[test]
+    fn map_to_curve() {
+        check(
+            map_fp_to_g1_run,
+            "bls_g1map_",
+            &input(&[FP]),
+            FP_TO_G1,
+            5_500,
+        );
+        check(
+            map_fp2_to_g2_run,
+            "bls_g2map_",
+            &input(&[FP2]),
+            FP2_TO_G2,
+            23_800,
+        );
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let g1 = input(&[G1]);
+        assert!(g1_add_run(&g1, u64::MAX).is_err(), "input too short");
+        assert!(g1_msm_run(&[], u64::MAX).is_err(), "empty msm");
+        assert!(pairing_run(&[], u64::MAX).is_err(), "empty pairing");
+
+        let mut bad_padding = g1.clone();
+        bad_padding[0] = 1;
+        assert_eq!(
+            g1_msm_run(&[bad_padding, scalar(1)].concat(), u64::MAX).unwrap_err(),
+            error("ERR_BLS12_INVALID_FP_PADDING")
+        );
+
+        let mut not_in_field = input(&[FP]);
+        not_in_field[PADDED_FP_LEN - FP_LEN..].copy_from_slice(&MODULUS);
+        assert_eq!(
+            map_fp_to_g1_run(&not_in_field, u64::MAX).unwrap_err(),
+            error("ERR_BLS12_FP_NOT_IN_FIELD")
+        );
+
+        let mut not_on_curve = g1.clone();
+        not_on_curve[127] ^= 1;
+        assert_eq!(
+            g1_add_run(&[not_on_curve, g1.clone()].concat(), u64::MAX).unwrap_err(),
+            error("ERR_BLS12_G1_NOT_ON_CURVE")
+        );
+
+        // Addition does not check subgroup, MSM and pairing do.
+        let not_in_subgroup = input(&[NOT_IN_SUBGROUP_G1]);
+        assert!(g1_add_run(&[not_in_subgroup.clone(), g1].concat(), u64::MAX).is_ok());
+        assert_eq!(
+            g1_msm_run(&[not_in_subgroup.clone(), scalar(1)].concat(), u64::MAX).unwrap_err(),
+            error("ERR_BLS12_G1_NOT_IN_SUBGROUP")
+        );
+        assert_eq!(
+            pairing_run(&[not_in_subgroup, input(&[G2])].concat(), u64::MAX).unwrap_err(),
+            error("ERR_BLS12_G1_NOT_IN_SUBGROUP")
+        );
+
+        assert_eq!(
+            g1_add_run(&input(&[G1, G1]), G1_ADD_GAS - 1).unwrap_err(),
+            Return::OutOfGas
+        );
+    }
+
+    #[test]
+    fn msm_gas() {
+        assert_eq!(msm_cost(1, G1_MUL_GAS, &G1_MSM_DISCOUNT), 12_000);
+        assert_eq!(msm_cost(2, G1_MUL_GAS, &G1_MSM_DISCOUNT), 22_776);
+        assert_eq!(
+            msm_cost(200, G1_MUL_GAS, &G1_MSM_DISCOUNT),
+            200 * 12_000 * 519 / 1000
+        );
+        assert_eq!(msm_cost(1, G2_MUL_GAS, &G2_MSM_DISCOUNT), 22_500);
+        assert_eq!(msm_cost(2, G2_MUL_GAS, &G2_MSM_DISCOUNT), 45_000);
+        assert_eq!(
+            msm_cost(200, G2_MUL_GAS, &G2_MSM_DISCOUNT),
+            200 * 22_500 * 524 / 1000
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! EIP-4844: Shard Blob Transactions, point evaluation precompile.
+use crate::{gas_query, Precompile, PrecompileOutput, PrecompileResult, Return};
+use alloc::{borrow::Cow, vec::Vec};
+use c_kzg::{Bytes32, Bytes48, KzgProof};
+pub use c_kzg::{Error as KzgError, KzgSettings};
+use primitive_types::H160 as Address;
+use sha2::{Digest, Sha256};
+
+pub const POINT_EVALUATION: (Address, Precompile) =
+    (crate::make_address(0, 0x0a), Precompile::Standard(run));
+
+pub const GAS_COST: u64 = 50_000;
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+pub const FIELD_ELEMENTS_PER_BLOB: u64 = 4096;
+/// Big endian BLS12-381 scalar field modulus.
+pub const BLS_MODULUS: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// Run point evaluation with bundled mainnet trusted setup.
+fn run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_with_settings(input, gas_limit, c_kzg::ethereum_kzg_settings())
+}
+
+/// Run point evaluation with given trusted setup.
+///
+/// Precompile with custom trusted setup can't be a function pointer, wrap this function in
+/// a closure that owns the settings and register it as stateful precompile on `EVM`.
+///
+/// Input is `versioned_hash || z || y || commitment || proof`, 192 bytes in total.
+pub fn run_with_settings(input: &[u8], gas_limit: u64, settings: &KzgSettings) -> PrecompileResult {
+    let cost = gas_query(GAS_COST, gas_limit)?;
+    if input.len() != 192 {
+        return Err(Return::Other(Cow::Borrowed(
+            "ERR_BLOB_INVALID_INPUT_LENGTH",
+        )));
+    }
+
+    let versioned_hash = &input[..32];
+    let commitment = &input[96..144];
+    if kzg_to_versioned_hash(commitment) != versioned_hash {
+        return Err(Return::Other(Cow::Borrowed("ERR_BLOB_MISMATCHED_VERSION")));
+    }
+
+    let z = Bytes32::from_bytes(&input[32..64]);
+    let y = Bytes32::from_bytes(&input[64..96]);
+    let commitment = Bytes48::from_bytes(commitment);
+    let proof = Bytes48::from_bytes(&input[144..192]);
+    let verified = match (z, y, commitment, proof) {
+        (Ok(z), Ok(y), Ok(commitment), Ok(proof)) => {
+            KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, settings).unwrap_or(false)
+        }
+        _ => false,
+    };
+    if !verified {
+        return Err(Return::Other(Cow::Borrowed(
+            "ERR_BLOB_VERIFY_KZG_PROOF_FAILED",
+        )));
+    }
+
+    // Return `FIELD_ELEMENTS_PER_BLOB || BLS_MODULUS` as 32 byte big endian numbers.
+    let mut output = vec![0u8; 64];
+    output[24..32].copy_from_slice(&FIELD_ELEMENTS_PER_BLOB.to_be_bytes());
+    output[32..].copy_from_slice(&BLS_MODULUS);
+    Ok(PrecompileOutput::without_logs(cost, output))
+}
+
+/// Load trusted setup from the text format used by c-kzg-4844 `trusted_setup.txt`: number of G1
+/// points, number of G2 points and then hex encoded G1 and G2 points, one per line.
+///
+/// Loaded settings can be used with [run_with_settings], for example on devnets and in tests that
+/// do not use the mainnet ceremony.
+pub fn load_trusted_setup(trusted_setup: &str) -> Result<KzgSettings, KzgError> {
+    KzgSettings::parse_kzg_trusted_setup(trusted_setup)
+}
+
+/// `VERSIONED_HASH_VERSION_KZG || sha256(commitment)[1..]`
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> Vec<u8> {
+    let mut hash = Sha256::digest(commitment).to_vec();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_evaluation() {
+        // Vector from c-kzg-4844 `verify_kzg_proof` tests.
+        let commitment = hex::decode("8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7").unwrap();
+        let z = hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000")
+            .unwrap();
+        let y = hex::decode("1522a4a7f34e1ea350ae07c29c96c7e79655aa926122e95fe69fcbd932ca49e9")
+            .unwrap();
+        let proof = hex::decode("a62ad71d14c5719385c0686f1871430475bf3a00f0aa3f7b8dd99a9abc2160744faf0070725e00b60ad9a026a15b1a8c").unwrap();
+        let input = [
+            kzg_to_versioned_hash(&commitment),
+            z,
+            y.clone(),
+            commitment.clone(),
+            proof.clone(),
+        ]
+        .concat();
+
+        let out = run(&input, GAS_COST).unwrap();
+        assert_eq!(out.cost, GAS_COST);
+        assert_eq!(
+            hex::encode(out.output),
+            "000000000000000000000000000000000000000000000000000000000000100073eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+
+        assert_eq!(run(&input, GAS_COST - 1).unwrap_err(), Return::OutOfGas);
+
+        let mut wrong_hash = input.clone();
+        wrong_hash[0] = 0;
+        assert!(run(&wrong_hash, GAS_COST).is_err());
+
+        let mut wrong_y = input.clone();
+        wrong_y[95] ^= 1;
+        assert!(run(&wrong_y, GAS_COST).is_err());
+    }
+
+    #[test]
+    fn point_evaluation_vectors() {
+        // `pointEvaluation.json` precompile vectors from go-ethereum.
+        let input = hex::decode("01e798154708fe7789429634053cbf9f99b619f9f084048927333fce637f549b564c0a11a0f704f4fc3e8acfe0f8245f0ad1347b378fbf96e206da11a5d3630624d25032e67a7e6a4910df5834b8fe70e6bcfeeac0352434196bdf4b2485d5a18f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7873033e038326e87ed3e1276fd140253fa08e9fc25fb2d9a98527fc22a2c9612fbeafdad446cbc7bcdbdcd780af2c16a").unwrap();
+        let out = run(&input, u64::MAX).unwrap();
+        assert_eq!(out.cost, 50_000);
+        assert_eq!(
+            hex::encode(out.output),
+            "000000000000000000000000000000000000000000000000000000000000100073eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+
+        // fail-pointEvaluation: input length, versioned hash and proof are checked.
+        assert_eq!(
+            run(&input[..191], u64::MAX).unwrap_err(),
+            Return::Other(Cow::Borrowed("ERR_BLOB_INVALID_INPUT_LENGTH"))
+        );
+        let mut wrong_version = input.clone();
+        wrong_version[0] = 0x02;
+        assert_eq!(
+            run(&wrong_version, u64::MAX).unwrap_err(),
+            Return::Other(Cow::Borrowed("ERR_BLOB_MISMATCHED_VERSION"))
+        );
+        let mut wrong_proof = input;
+        wrong_proof[191] ^= 1;
+        assert_eq!(
+            run(&wrong_proof, u64::MAX).unwrap_err(),
+            Return::Other(Cow::Borrowed("ERR_BLOB_VERIFY_KZG_PROOF_FAILED"))
+        );
+    }
+
+    #[test]
+    fn load_invalid_trusted_setup() {
+        assert!(load_trusted_setup("").is_err());
+        assert!(load_trusted_setup("1\n1\nzz\nzz\n").is_err());
+        // Number of points has to match the mainnet ceremony.
+        let g1 = "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb";
+        assert!(load_trusted_setup(&format!("1\n0\n{}\n", g1)).is_err());
+    }
+}
@@ -5,14 +5,21 @@ use once_cell::sync::OnceCell;
 use primitive_types::{H160 as Address, H256, U256};
 
 mod blake2;
+#[cfg(feature = "bls12_381")]
+pub mod bls12_381;
 mod bn128;
 mod error;
 mod hash;
 mod identity;
+#[cfg(feature = "kzg")]
+pub mod kzg_point_evaluation;
 mod modexp;
 mod secp256k1;
+#[cfg(feature = "secp256r1")]
+pub mod secp256r1;
 
 pub use error::Return;
+pub use secp256k1::{ecrecover, secret_key_to_address};
 
 /// libraries for no_std flag
 #[macro_use]
@@ -95,7 +102,8 @@ pub enum SpecId {
     BYZANTIUM = 1,
     ISTANBUL = 2,
     BERLIN = 3,
-    LATEST = 4,
+    CANCUN = 4,
+    LATEST = 5,
 }
 
 impl SpecId {
@@ -174,8 +182,37 @@ impl Precompiles {
         })
     }
 
+    /// EIP-4844 point evaluation precompile at `0x0a` needs `kzg` feature. Without it the address
+    /// is still a precompile, so it is warm and its account is not created, but calling it always
+    /// fails with an error.
+    pub fn cancun() -> &'static Self {
+        static INSTANCE: OnceCell<Precompiles> = OnceCell::new();
+        INSTANCE.get_or_init(|| {
+            let mut precompiles = Self::berlin().clone();
+            // EIP-4844: Shard Blob Transactions
+            #[cfg(feature = "kzg")]
+            precompiles
+                .fun
+                .extend([kzg_point_evaluation::POINT_EVALUATION]);
+            #[cfg(not(feature = "kzg"))]
+            precompiles.fun.insert(
+                make_address(0, 0x0a),
+                Precompile::Standard(point_evaluation_disabled),
+            );
+            precompiles
+        })
+    }
+
     pub fn latest() -> &'static Self {
-        Self::berlin()
+        static INSTANCE: OnceCell<Precompiles> = OnceCell::new();
+        INSTANCE.get_or_init(|| {
+            #[allow(unused_mut)]
+            let mut precompiles = Self::cancun().clone();
+            // EIP-2537: Precompile for BLS12-381 curve operations
+            #[cfg(feature = "bls12_381")]
+            precompiles.fun.extend(bls12_381::precompiles());
+            precompiles
+        })
     }
 
     pub fn new(spec: SpecId) -> &'static Self {
@@ -184,6 +221,7 @@ impl Precompiles {
             SpecId::BYZANTIUM => Self::byzantium(),
             SpecId::ISTANBUL => Self::istanbul(),
             SpecId::BERLIN => Self::berlin(),
+            SpecId::CANCUN => Self::cancun(),
             SpecId::LATEST => Self::latest(),
         }
     }
@@ -201,6 +239,20 @@ impl Precompiles {
         self.fun.get(address).cloned()
     }
 
+    /// Add precompile at given address. Returns previous precompile if there was one.
+    pub fn insert(&mut self, address: Address, precompile: Precompile) -> Option<Precompile> {
+        self.fun.insert(address, precompile)
+    }
+
+    /// Remove precompile from given address.
+    pub fn remove(&mut self, address: &Address) -> Option<Precompile> {
+        self.fun.remove(address)
+    }
+
+    pub fn extend(&mut self, precompiles: impl IntoIterator<Item = (Address, Precompile)>) {
+        self.fun.extend(precompiles)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.fun.len() == 0
     }
@@ -210,6 +262,13 @@ impl Precompiles {
     }
 }
 
+#[cfg(not(feature = "kzg"))]
+fn point_evaluation_disabled(_input: &[u8], _gas_limit: u64) -> PrecompileResult {
+    Err(Return::Other(alloc::borrow::Cow::Borrowed(
+        "point evaluation precompile requires kzg feature",
+    )))
+}
+
 /// const fn for making an address by concatenating the bytes from two given numbers,
 /// Note that 32 + 128 = 160 = 20 bytes (the length of an address). This function is used
 /// as a convenience for specifying the addresses of the various precompiles.
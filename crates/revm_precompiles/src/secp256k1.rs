@@ -16,7 +16,7 @@ pub const ECRECOVER: (Address, Precompile) = (
 mod secp256k1 {
     use core::convert::TryFrom;
     use k256::{
-        ecdsa::{recoverable, Error},
+        ecdsa::{recoverable, Error, SigningKey},
         elliptic_curve::sec1::ToEncodedPoint,
         PublicKey as K256PublicKey,
     };
@@ -26,13 +26,23 @@ mod secp256k1 {
     pub fn ecrecover(sig: &[u8; 65], msg: &[u8; 32]) -> Result<Address, Error> {
         let sig = recoverable::Signature::try_from(sig.as_ref())?;
         let verify_key = sig.recover_verifying_key_from_digest_bytes(msg.into())?;
-        let public_key = K256PublicKey::from(&verify_key);
+        Ok(public_key_to_address(&K256PublicKey::from(&verify_key)))
+    }
+
+    pub fn secret_key_to_address(secret_key: &[u8; 32]) -> Result<Address, Error> {
+        let signing_key = SigningKey::from_bytes(secret_key)?;
+        Ok(public_key_to_address(&K256PublicKey::from(
+            &signing_key.verifying_key(),
+        )))
+    }
+
+    fn public_key_to_address(public_key: &K256PublicKey) -> Address {
         let public_key = public_key.to_encoded_point(/* compress = */ false);
         let public_key = public_key.as_bytes();
         let hash = Keccak256::digest(&public_key[1..]);
         let mut address = Address::zero();
         address.as_bytes_mut().copy_from_slice(&hash[12..]);
-        Ok(address)
+        address
     }
 }
 
@@ -42,7 +52,7 @@ mod secp256k1 {
     use primitive_types::H160 as Address;
     use secp256k1::{
         ecdsa::{RecoverableSignature, RecoveryId},
-        Message, Secp256k1,
+        Message, PublicKey, Secp256k1, SecretKey,
     };
     use sha3::{Digest, Keccak256};
 
@@ -52,13 +62,37 @@ mod secp256k1 {
 
         let secp = Secp256k1::new();
         let public = secp.recover_ecdsa(&Message::from_slice(&msg[..32])?, &sig)?;
+        Ok(public_key_to_address(&public))
+    }
+
+    pub fn secret_key_to_address(secret_key: &[u8; 32]) -> Result<Address, secp256k1::Error> {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(secret_key)?;
+        Ok(public_key_to_address(&PublicKey::from_secret_key(
+            &secp,
+            &secret_key,
+        )))
+    }
 
+    fn public_key_to_address(public: &PublicKey) -> Address {
         let mut out = vec![0; 20];
         out.copy_from_slice(&Keccak256::digest(&public.serialize_uncompressed()[1..])[12..]);
-        Ok(Address::from_slice(&out))
+        Address::from_slice(&out)
     }
 }
 
+/// Recover address of signer from 65 bytes signature (`r || s || recovery_id`) and message hash.
+/// Recovery id is expected to be `0` or `1`. Returns `None` if signature is invalid.
+pub fn ecrecover(sig: &[u8; 65], msg: &[u8; 32]) -> Option<Address> {
+    secp256k1::ecrecover(sig, msg).ok()
+}
+
+/// Derive address of the account from its 32 bytes secp256k1 private key.
+/// Returns `None` if private key is zero or not below the curve order.
+pub fn secret_key_to_address(secret_key: &[u8; 32]) -> Option<Address> {
+    secp256k1::secret_key_to_address(secret_key).ok()
+}
+
 fn ec_recover_run(i: &[u8], target_gas: u64) -> PrecompileResult {
     let cost = gas_query(ECRECOVER_BASE, target_gas)?;
     let mut input = [0u8; 128];
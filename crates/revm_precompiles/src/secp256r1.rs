@@ -0,0 +1,100 @@
+//! RIP-7212: Precompile for secp256r1 (P-256) curve support.
+//!
+//! It is not part of any Ethereum fork, add it to custom precompile set if chain supports it.
+use crate::{gas_query, Precompile, PrecompileOutput, PrecompileResult, StandardPrecompileFn};
+use alloc::vec::Vec;
+use ecdsa::hazmat::VerifyPrimitive;
+use p256::{
+    ecdsa::Signature, elliptic_curve::sec1::FromEncodedPoint, EncodedPoint, FieldBytes, PublicKey,
+};
+use primitive_types::H160 as Address;
+
+const P256VERIFY_BASE: u64 = 3_450;
+
+pub const P256VERIFY: (Address, Precompile) = (
+    super::make_address(0, 0x100),
+    Precompile::Standard(p256_verify_run as StandardPrecompileFn),
+);
+
+/// Input is `hash || r || s || x || y`, 160 bytes in total. Returns 32 bytes with value one if
+/// signature is valid, otherwise returns empty output. Invalid input does not return an error.
+fn p256_verify_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let cost = gas_query(P256VERIFY_BASE, gas_limit)?;
+    let out = if verify(input).is_some() {
+        let mut out = vec![0u8; 32];
+        out[31] = 1;
+        out
+    } else {
+        Vec::new()
+    };
+    Ok(PrecompileOutput::without_logs(cost, out))
+}
+
+fn verify(input: &[u8]) -> Option<()> {
+    if input.len() != 160 {
+        return None;
+    }
+    let msg = &input[..32];
+    let signature = Signature::from_scalars(
+        <[u8; 32]>::try_from(&input[32..64]).ok()?,
+        <[u8; 32]>::try_from(&input[64..96]).ok()?,
+    )
+    .ok()?;
+    let point =
+        EncodedPoint::from_affine_coordinates(input[96..128].into(), input[128..160].into(), false);
+    let public_key = Option::<PublicKey>::from(PublicKey::from_encoded_point(&point))?;
+    public_key
+        .as_affine()
+        .verify_prehashed(FieldBytes::clone_from_slice(msg), &signature)
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::{signature::Signer, SigningKey};
+    use sha2::{Digest, Sha256};
+
+    fn signed_input() -> Vec<u8> {
+        let signing_key = SigningKey::from_bytes(&[0x42; 32]).unwrap();
+        let message = b"revm";
+        let signature: Signature = signing_key.sign(message);
+        // signing key hashes message with sha256 before signing.
+        let msg = Sha256::digest(message);
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        [
+            &msg[..],
+            &signature.r().to_bytes(),
+            &signature.s().to_bytes(),
+            point.x().unwrap(),
+            point.y().unwrap(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn valid_signature() {
+        let out = p256_verify_run(&signed_input(), P256VERIFY_BASE).unwrap();
+        assert_eq!(out.cost, P256VERIFY_BASE);
+        let mut expected = vec![0u8; 32];
+        expected[31] = 1;
+        assert_eq!(out.output, expected);
+    }
+
+    #[test]
+    fn invalid_signature() {
+        let mut input = signed_input();
+        input[0] ^= 1;
+        assert!(p256_verify_run(&input, u64::MAX).unwrap().output.is_empty());
+        // public key not on curve
+        let mut input = signed_input();
+        input[159] ^= 1;
+        assert!(p256_verify_run(&input, u64::MAX).unwrap().output.is_empty());
+        // wrong input length
+        assert!(p256_verify_run(&input[..159], u64::MAX)
+            .unwrap()
+            .output
+            .is_empty());
+        assert!(p256_verify_run(&input, P256VERIFY_BASE - 1).is_err());
+    }
+}
@@ -3,10 +3,7 @@ use core::convert::TryInto;
 use bn_rs::BN;
 use bytes::Bytes;
 use primitive_types::{H160, U256};
-use revm::{
-    AccountInfo, Bytecode, DatabaseCommit, ExecutionResult, InMemoryDB, SpecId, TransactTo,
-    EVM as rEVM,
-};
+use revm::{AccountInfo, Bytecode, DatabaseCommit, InMemoryDB, SpecId, TransactTo, EVM as rEVM};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -47,29 +44,18 @@ impl EVM {
         evm
     }
 
-    pub fn transact(&mut self) -> u64 {
-        let (
-            ExecutionResult {
-                exit_reason,
-                out,
-                gas_used,
-                gas_refunded,
-                logs,
-                ..
-            },
-            state,
-        ) = self.revm.transact();
+    pub fn transact(&mut self) -> Result<u64, JsValue> {
+        let (exec_result, state) = self
+            .revm
+            .transact()
+            .map_err(|err| JsValue::from_str(&format!("{:?}", err)))?;
         console_log!(
-            "Transact done, exit:{:?}, gas:{:?} ({:?} refunded), data:{:?}\nstate_chage:{:?}\nlogs:{:?}",
-            exit_reason,
-            gas_used,
-            gas_refunded,
-            out,
+            "Transact done, result:{:?}\nstate_chage:{:?}",
+            exec_result,
             state,
-            logs,
         );
         self.revm.db().unwrap().commit(state);
-        gas_used
+        Ok(exec_result.gas_used())
     }
 
     /****** DATABASE RELATED ********/